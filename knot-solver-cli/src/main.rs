@@ -1,19 +1,66 @@
 #[macro_use]
 extern crate serde_derive;
 
-use knot_solver::{Knot, KnotParseError};
+use knot_solver::{
+    braid::Braid,
+    cache::{CacheKey, Invariant, InvariantCache},
+    polynomial::{Format, Polynomial},
+    BracketMethod, ComputeError, ComputeOptions, Knot, PartialBracket,
+};
 use std::{
     error::Error,
-    io,
+    fs::File,
+    io::{self, BufReader, Write},
     path::{Path, PathBuf},
+    process,
     str::FromStr,
     string::ToString,
+    sync::Arc,
+    time::Duration,
 };
 use structopt::StructOpt;
 
 /// Computes polynomial representations of knots specified in braid notation.
 #[derive(StructOpt, Debug)]
 struct Opt {
+    /// The output format for polynomials: plain, pretty, latex, mathml, mathematica or sage.
+    #[structopt(
+        short = "f",
+        long = "format",
+        default_value = "plain",
+        raw(global = "true")
+    )]
+    format: Format,
+
+    /// The method used to compute bracket polynomials: states, tait or decomposition.
+    #[structopt(
+        short = "m",
+        long = "method",
+        default_value = "states",
+        raw(global = "true")
+    )]
+    method: BracketMethod,
+
+    /// Gives up on a polynomial after this many seconds.
+    #[structopt(
+        long = "timeout",
+        parse(try_from_str = "parse_timeout"),
+        raw(global = "true")
+    )]
+    timeout: Option<Duration>,
+
+    /// The number of threads to compute with. One thread computes everything in order.
+    #[structopt(short = "j", long = "threads", raw(global = "true"))]
+    threads: Option<usize>,
+
+    /// Reports the progress of long computations on stderr.
+    #[structopt(long = "progress", raw(global = "true"))]
+    progress: bool,
+
+    /// Simplifies braids with Markov moves before computing their polynomials.
+    #[structopt(long = "simplify", raw(global = "true"))]
+    simplify: bool,
+
     #[structopt(subcommand)]
     command: Command,
 }
@@ -24,21 +71,36 @@ enum Command {
     #[structopt(name = "bracket")]
     Bracket {
         /// The braid representation of the knot.
-        braid: String
+        braid: String,
     },
 
     /// Computes the beta polynomial of a given knot.
     #[structopt(name = "beta")]
     Beta {
         /// The braid representation of the knot.
-        braid: String
+        braid: String,
     },
 
     /// Computes the jones polynomial of a given knot.
     #[structopt(name = "jones")]
     Jones {
         /// The braid representation of the knot.
-        braid: String
+        braid: String,
+    },
+
+    /// Prints the Tait graph of a given knot as an edge list.
+    #[structopt(name = "tait")]
+    Tait {
+        /// The braid representation of the knot.
+        braid: String,
+    },
+
+    /// Simplifies a given braid with Markov moves, printing the moves and then the simplified
+    /// braid.
+    #[structopt(name = "simplify")]
+    Simplify {
+        /// The braid representation of the knot.
+        braid: String,
     },
 
     /// Generates a csv file with all of the polynomials for all of the given knots.
@@ -48,28 +110,149 @@ enum Command {
         #[structopt(short = "o", long = "output")]
         output: Option<PathBuf>,
 
+        /// Computes every polynomial instead of reusing results stored by earlier runs.
+        #[structopt(long = "no-cache")]
+        no_cache: bool,
+
+        /// The directory to store results in. Defaults to $KNOT_SOLVER_CACHE, or a knot-solver
+        /// directory in the user's cache directory.
+        #[structopt(long = "cache-dir")]
+        cache_dir: Option<PathBuf>,
+
         /// The list of braids to compute for.
         braids: Vec<String>,
     },
+
+    /// Counts one share of the states of a given knot, to be combined with `merge`.
+    #[structopt(name = "shard")]
+    Shard {
+        /// Which share of the states to count, starting from 0.
+        #[structopt(short = "i", long = "index")]
+        index: u128,
+
+        /// The number of shares the states are split into.
+        #[structopt(short = "n", long = "count")]
+        count: u128,
+
+        /// Optional output file. The result will be printed to stdout if not specified.
+        #[structopt(short = "o", long = "output")]
+        output: Option<PathBuf>,
+
+        /// The braid representation of the knot.
+        braid: String,
+    },
+
+    /// Combines the results of `shard` and prints a polynomial of the knot.
+    #[structopt(name = "merge")]
+    Merge {
+        /// The polynomial to print: bracket, beta or jones.
+        #[structopt(
+            long = "invariant",
+            default_value = "bracket",
+            raw(possible_values = r#"&["bracket", "beta", "jones"]"#)
+        )]
+        invariant: String,
+
+        /// The files written by `shard`.
+        #[structopt(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+/// Parses a number of seconds to wait for, which must be finite and not negative.
+fn parse_timeout(seconds: &str) -> Result<Duration, String> {
+    let value = f64::from_str(seconds).map_err(|e| e.to_string())?;
+    Duration::try_from_secs_f64(value)
+        .map_err(|_| format!("{} is not a number of seconds", seconds))
 }
 
 fn main() {
     let opt = Opt::from_args();
+    let format = opt.format;
+    let simplify = opt.simplify;
+    let mut options = ComputeOptions::new().method(opt.method);
+    if let Some(timeout) = opt.timeout {
+        options = options.time_limit(timeout);
+    }
+    match opt.threads {
+        Some(1) => options = options.sequential(),
+        Some(threads) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .expect("Could not start threads");
+            options = options.thread_pool(Arc::new(pool));
+        }
+        None => {}
+    }
+    if opt.progress {
+        options = options.on_progress(|p| match p.total {
+            Some(total) => eprint!("\r{} / {}", p.done, total),
+            None => eprint!("\r{}", p.done),
+        });
+    }
+
+    let result: Result<(), Box<dyn Error>> = match opt.command {
+        Command::Bracket { braid } => polynomial(&braid, Invariant::Bracket, simplify, &options)
+            .map(|p| println!("{}", p.format(format)))
+            .map_err(Into::into),
+        Command::Beta { braid } => polynomial(&braid, Invariant::Beta, simplify, &options)
+            .map(|p| println!("{}", p.format(format)))
+            .map_err(Into::into),
+        Command::Jones { braid } => polynomial(&braid, Invariant::Jones, simplify, &options)
+            .map(|p| println!("{}", p.format(format)))
+            .map_err(Into::into),
+        Command::Tait { braid } => {
+            print!(
+                "{}",
+                Knot::from_str(braid.as_str())
+                    .expect("Invalid braid")
+                    .tait_graph()
+                    .edge_list()
+            );
+            Ok(())
+        }
+        Command::Simplify { braid } => {
+            let simplification = Braid::from_str(braid.as_str())
+                .expect("Invalid braid")
+                .simplify();
+            for m in simplification.moves() {
+                println!("{}", m);
+            }
+            println!("{}", simplification.braid());
+            Ok(())
+        }
+        Command::Csv {
+            output,
+            no_cache,
+            cache_dir,
+            braids,
+        } => {
+            let cache = if no_cache {
+                None
+            } else {
+                cache_dir
+                    .or_else(InvariantCache::default_location)
+                    .and_then(|dir| InvariantCache::open(dir).ok())
+            };
+            run_csv(output, braids, format, simplify, &options, cache.as_ref())
+        }
+        Command::Shard {
+            index,
+            count,
+            output,
+            braid,
+        } => run_shard(&braid, index, count, output, &options),
+        Command::Merge { invariant, files } => run_merge(&invariant, files, format),
+    };
 
-    match opt.command {
-        Command::Bracket { braid } => println!(
-            "{}",
-            Knot::from_str(braid.as_str()).expect("Invalid braid").bracket_polynomial()
-        ),
-        Command::Beta { braid } => println!(
-            "{}",
-            Knot::from_str(braid.as_str()).expect("Invalid braid").beta_polynomial()
-        ),
-        Command::Jones { braid } => println!(
-            "{}",
-            Knot::from_str(braid.as_str()).expect("Invalid braid").jones_polynomial()
-        ),
-        Command::Csv { output, braids } => run_csv(output, braids).expect("Invalid braid"),
+    if opt.progress {
+        eprintln!();
+    }
+    if let Err(e) = result {
+        let _ = io::stdout().flush();
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
 
@@ -82,32 +265,88 @@ struct KnotRecord {
 }
 
 impl KnotRecord {
-    fn with_braid(braid: String) -> Result<Self, KnotParseError> {
-        let knot = Knot::from_str(braid.as_str())?;
+    fn with_braid(
+        braid: String,
+        format: Format,
+        simplify: bool,
+        options: &ComputeOptions,
+        cache: Option<&InvariantCache>,
+    ) -> Result<Self, ComputeError> {
+        let key = CacheKey::braid(&braid);
+        let show = |invariant: Invariant| -> Result<String, ComputeError> {
+            let p = match cache.and_then(|cache| cache.get(&key, invariant)) {
+                Some(p) => p,
+                None => {
+                    let p = polynomial(&braid, invariant, simplify, options)?;
+                    if let Some(cache) = cache {
+                        let _ = cache.insert(&key, invariant, &p);
+                    }
+                    p
+                }
+            };
+            Ok(p.format(format).to_string())
+        };
         Ok(KnotRecord {
+            bracket: show(Invariant::Bracket)?,
+            beta: show(Invariant::Beta)?,
+            jones: show(Invariant::Jones)?,
             braid,
-            bracket: knot.bracket_polynomial().to_string(),
-            beta: knot.beta_polynomial().to_string(),
-            jones: knot.jones_polynomial().to_string(),
         })
     }
 }
 
-fn run_csv<P: AsRef<Path>>(output_path: Option<P>, braids: Vec<String>) -> Result<(), Box<Error>> {
+/// Computes a polynomial of the closure of a braid, using a simpler braid with the same closure
+/// if `simplify` is set.
+fn polynomial(
+    braid: &str,
+    invariant: Invariant,
+    simplify: bool,
+    options: &ComputeOptions,
+) -> Result<Polynomial, ComputeError> {
+    if simplify {
+        let simplification = Braid::from_str(braid).expect("Invalid braid").simplify();
+        match invariant {
+            Invariant::Bracket => simplification.try_bracket_polynomial(options),
+            Invariant::Beta => simplification.try_beta_polynomial(options),
+            Invariant::Jones => simplification.try_jones_polynomial(options),
+        }
+    } else {
+        let knot = Knot::from_str(braid).expect("Invalid braid");
+        match invariant {
+            Invariant::Bracket => knot.try_bracket_polynomial(options),
+            Invariant::Beta => knot.try_beta_polynomial(options),
+            Invariant::Jones => knot.try_jones_polynomial(options),
+        }
+    }
+}
+
+fn run_csv<P: AsRef<Path>>(
+    output_path: Option<P>,
+    braids: Vec<String>,
+    format: Format,
+    simplify: bool,
+    options: &ComputeOptions,
+    cache: Option<&InvariantCache>,
+) -> Result<(), Box<dyn Error>> {
     if let Some(path) = output_path {
         let wtr = csv::Writer::from_path(path)?;
-        write_csv(wtr, braids)
+        write_csv(wtr, braids, format, simplify, options, cache)
     } else {
         let wtr = csv::Writer::from_writer(io::stdout());
-        write_csv(wtr, braids)
+        write_csv(wtr, braids, format, simplify, options, cache)
     }
 }
 
-fn write_csv<T: io::Write>(mut wtr: csv::Writer<T>, braids: Vec<String>) -> Result<(), Box<Error>> {
-    for record in braids
-        .into_iter()
-        .map(|braid| KnotRecord::with_braid(braid.to_string()).unwrap())
-    {
+fn write_csv<T: io::Write>(
+    mut wtr: csv::Writer<T>,
+    braids: Vec<String>,
+    format: Format,
+    simplify: bool,
+    options: &ComputeOptions,
+    cache: Option<&InvariantCache>,
+) -> Result<(), Box<dyn Error>> {
+    for braid in braids {
+        let record = KnotRecord::with_braid(braid, format, simplify, options, cache)?;
         wtr.serialize(record)?;
     }
 
@@ -115,3 +354,55 @@ fn write_csv<T: io::Write>(mut wtr: csv::Writer<T>, braids: Vec<String>) -> Resu
 
     Ok(())
 }
+
+fn run_shard(
+    braid: &str,
+    index: u128,
+    count: u128,
+    output: Option<PathBuf>,
+    options: &ComputeOptions,
+) -> Result<(), Box<dyn Error>> {
+    if index >= count {
+        return Err(format!("shard {} of {} does not exist", index, count).into());
+    }
+    let knot = Knot::from_str(braid).expect("Invalid braid");
+    let partial = knot.try_partial_bracket(knot.shard_range(index, count), options)?;
+    match output {
+        Some(path) => serde_json::to_writer(File::create(path)?, &partial)?,
+        None => println!("{}", serde_json::to_string(&partial)?),
+    }
+    Ok(())
+}
+
+fn run_merge(invariant: &str, files: Vec<PathBuf>, format: Format) -> Result<(), Box<dyn Error>> {
+    let mut merged: Option<PartialBracket> = None;
+    for path in files {
+        let partial: PartialBracket = serde_json::from_reader(BufReader::new(File::open(&path)?))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        merged = Some(match merged {
+            Some(merged) => merged.merge(partial)?,
+            None => partial,
+        });
+    }
+    let merged = merged.expect("at least one file is required");
+
+    let polynomial = match invariant {
+        "beta" => merged.beta_polynomial(),
+        "jones" => merged.jones_polynomial(),
+        _ => merged.bracket_polynomial(),
+    };
+    match polynomial {
+        Some(p) => {
+            println!("{}", p.format(format));
+            Ok(())
+        }
+        None => {
+            let missing: Vec<String> = merged
+                .missing()
+                .iter()
+                .map(|r| format!("{}..{}", r.start, r.end))
+                .collect();
+            Err(format!("states {} have not been counted", missing.join(", ")).into())
+        }
+    }
+}
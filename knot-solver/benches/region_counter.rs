@@ -16,11 +16,34 @@ fn simulate(crossings: usize, regions: usize) {
     }
 }
 
+fn simulate_reused(counter: &mut RegionCounter, crossings: usize, regions: usize) {
+    counter.reset();
+
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..crossings {
+        let uno = rng.gen_range(1usize, regions);
+        let dos = rng.gen_range(1usize, regions);
+
+        counter.combine(uno, dos);
+    }
+}
+
 fn basic_benchmark(c: &mut Criterion) {
     c.bench_function("basics 1", |b| b.iter(|| simulate(150, 100)));
 
     c.bench_function("basics 2", |b| b.iter(|| simulate(150, 250)));
 
+    c.bench_function("reused 1", |b| {
+        let mut counter = RegionCounter::new(100);
+        b.iter(move || simulate_reused(&mut counter, 150, 100))
+    });
+
+    c.bench_function("reused 2", |b| {
+        let mut counter = RegionCounter::new(250);
+        b.iter(move || simulate_reused(&mut counter, 150, 250))
+    });
+
     c.bench_function("rand test", |b| {
         let mut rng = rand::thread_rng();
 
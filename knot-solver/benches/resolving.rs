@@ -1,38 +1,53 @@
-use knot_solver::Knot;
-use std::str::FromStr;
+use knot_solver::{random, Enumeration, Knot};
 
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
-use rand::Rng;
+use rand::rngs::StdRng;
 
-fn simulate(input: String) {
-    let knot = Knot::from_str(input.as_str()).unwrap();
-    let res = knot.resolutions();
+fn simulate(knot: Knot) {
+    knot.resolutions();
 }
 
-fn gen_input(len: u8) -> String {
-    let mut s = String::new();
-    let mut rng = rand::thread_rng();
-    for _ in 0..len {
-        s.push(rng.gen_range(65u8, 91u8) as char);
-    }
-    s
+fn simulate_gray(knot: Knot) {
+    knot.resolutions_with(Enumeration::GrayCode);
+}
+
+fn gen_input(rng: &mut StdRng, len: usize) -> Knot {
+    random::braid(rng, 27, len).closure()
 }
 
 fn basic_benchmark(c: &mut Criterion) {
     c.bench_function("10 crossings", move |b| {
+        let mut rng = random::seeded(10);
         b.iter_batched(
-            || gen_input(10),
-            |input| simulate(input),
+            || gen_input(&mut rng, 10),
+            simulate,
             BatchSize::NumIterations(50),
         )
     });
+    c.bench_function("16 crossings", move |b| {
+        let mut rng = random::seeded(16);
+        b.iter_batched(
+            || gen_input(&mut rng, 16),
+            simulate,
+            BatchSize::NumIterations(10),
+        )
+    });
+    c.bench_function("16 crossings gray code", move |b| {
+        let mut rng = random::seeded(16);
+        b.iter_batched(
+            || gen_input(&mut rng, 16),
+            simulate_gray,
+            BatchSize::NumIterations(10),
+        )
+    });
+    let mut rng = random::seeded(0);
     c.bench_function_over_inputs(
         "Various crossing quantities",
         move |b, input| b.iter(|| simulate(input.clone())),
-        vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
             .iter()
-            .map(|i| gen_input(*i))
-            .collect::<Vec<String>>(),
+            .map(|&i| gen_input(&mut rng, i))
+            .collect::<Vec<Knot>>(),
     );
 }
 
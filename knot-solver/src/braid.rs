@@ -0,0 +1,457 @@
+//! Braids and their closures.
+//!
+//! A braid on `n` strands is a word in the Artin generators `σ1, ..., σ(n-1)`, where `σi`
+//! crosses strand `i` over strand `i + 1`. Words are stored as signed, 1-based generator
+//! indices, so `σ2^-1` is `-2`. In the letter notation used by `Knot::from_str`, `a` is `σ1`, `b`
+//! is `σ2` and so on, with capital letters for the inverses.
+
+pub use crate::garside::NormalForm;
+pub use crate::markov::{MarkovMove, Simplification};
+use crate::{CrossingBuilder, Knot, KnotParseError, Orientation};
+use std::{fmt, ops::Mul, str::FromStr};
+
+/// A braid, given by its number of strands and a word in the Artin generators.
+///
+/// ```
+/// use knot_solver::{braid::Braid, Knot};
+/// use std::str::FromStr;
+///
+/// let ab: Braid = "ab".parse().unwrap();
+/// let trefoil = ab.pow(2).closure();
+/// assert_eq!(
+///     trefoil.jones_polynomial(),
+///     Knot::from_str("abab").unwrap().jones_polynomial()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Braid {
+    strands: usize,
+    word: Vec<isize>,
+}
+
+impl Braid {
+    /// Creates a braid on the given number of strands from a word of signed, 1-based generator
+    /// indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the word contains `0`, or a generator that needs more strands.
+    pub fn new(strands: usize, word: Vec<isize>) -> Self {
+        for &g in &word {
+            assert!(
+                g != 0 && g.unsigned_abs() < strands,
+                "σ{} is not a generator of the braid group on {} strands",
+                g.abs(),
+                strands
+            );
+        }
+        Braid { strands, word }
+    }
+
+    /// Creates the braid with no crossings on the given number of strands.
+    pub fn identity(strands: usize) -> Self {
+        Braid::new(strands, Vec::new())
+    }
+
+    /// Creates the braid `σi` on the given number of strands.
+    pub fn generator(strands: usize, i: usize) -> Self {
+        Braid::new(strands, vec![i as isize])
+    }
+
+    /// Creates the full twist `(σ1 σ2 ... σ(n-1))^n`, which rotates the strands by a full turn.
+    pub fn full_twist(strands: usize) -> Self {
+        let cycle = Braid::new(strands, (1..strands as isize).collect());
+        cycle.pow(strands as i32)
+    }
+
+    /// Returns the number of strands.
+    pub fn strands(&self) -> usize {
+        self.strands
+    }
+
+    /// Returns the word of the braid as signed, 1-based generator indices.
+    pub fn word(&self) -> &[isize] {
+        &self.word
+    }
+
+    /// Returns the number of letters in the word.
+    pub fn len(&self) -> usize {
+        self.word.len()
+    }
+
+    /// Returns whether the word has no letters.
+    pub fn is_empty(&self) -> bool {
+        self.word.is_empty()
+    }
+
+    /// Returns the number of positive letters minus the number of negative letters, which is
+    /// the writhe of the closure.
+    pub fn exponent_sum(&self) -> isize {
+        self.word.iter().map(|g| g.signum()).sum()
+    }
+
+    /// Returns the inverse braid, which undoes this one.
+    pub fn inverse(&self) -> Self {
+        Braid::new(self.strands, self.word.iter().rev().map(|g| -g).collect())
+    }
+
+    /// Returns the braid composed with itself `n` times, using the inverse for negative `n`.
+    pub fn pow(&self, n: i32) -> Self {
+        let base = if n < 0 { self.inverse() } else { self.clone() };
+        let word = base.word.repeat(n.unsigned_abs() as usize);
+        Braid::new(self.strands, word)
+    }
+
+    /// Returns the mirror image of the braid, with every crossing changed.
+    pub fn mirror(&self) -> Self {
+        Braid::new(self.strands, self.word.iter().map(|g| -g).collect())
+    }
+
+    /// Returns the braid with its word read backwards. Its closure is the closure of this braid
+    /// with the orientation of every strand reversed.
+    pub fn reverse(&self) -> Self {
+        Braid::new(self.strands, self.word.iter().rev().cloned().collect())
+    }
+
+    /// Returns the braid with every generator that is next to its own inverse cancelled,
+    /// repeatedly, so that the word has no `σi σi^-1` or `σi^-1 σi` left.
+    pub fn free_reduce(&self) -> Self {
+        let mut word = Vec::with_capacity(self.word.len());
+        for &g in &self.word {
+            if word.last() == Some(&-g) {
+                word.pop();
+            } else {
+                word.push(g);
+            }
+        }
+        Braid::new(self.strands, word)
+    }
+
+    /// Returns an equivalent word found by Dehornoy's handle reduction.
+    ///
+    /// A `σi`-handle is a subword `σi^e v σi^-e` in which `v` only uses generators above `σi`.
+    /// Reducing it removes the outer letters and replaces each `σ(i+1)^d` in `v` by
+    /// `σ(i+1)^-e σi^d σ(i+1)^e`, which gives the same braid. Handles are reduced, first the one
+    /// that ends earliest, until there are none left. The result is empty exactly when the braid
+    /// is trivial, and otherwise its lowest generator only appears with one sign. It is not
+    /// always shorter than the original word.
+    pub fn handle_reduce(&self) -> Self {
+        let mut word = self.free_reduce().word;
+        while let Some((start, end)) = first_handle(&word) {
+            let e = word[start].signum();
+            let i = word[start].abs();
+            let mut reduced = Vec::with_capacity(word.len() + 2 * (end - start));
+            reduced.extend_from_slice(&word[..start]);
+            for &g in &word[start + 1..end] {
+                if g.abs() == i + 1 {
+                    reduced.extend_from_slice(&[-e * (i + 1), g.signum() * i, e * (i + 1)]);
+                } else {
+                    reduced.push(g);
+                }
+            }
+            reduced.extend_from_slice(&word[end + 1..]);
+            word = Braid::new(self.strands, reduced).free_reduce().word;
+        }
+        Braid::new(self.strands, word)
+    }
+
+    /// Returns whether the braid is the identity, decided by handle reduction.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// let aba: Braid = "aba".parse().unwrap();
+    /// let bab: Braid = "bab".parse().unwrap();
+    /// assert!((&aba * &bab.inverse()).is_trivial());
+    /// assert!(!"abAB".parse::<Braid>().unwrap().is_trivial());
+    /// ```
+    pub fn is_trivial(&self) -> bool {
+        self.handle_reduce().is_empty()
+    }
+
+    /// Returns the shortest equivalent word found by free and handle reduction, which has a
+    /// closure with no more crossings than this braid's.
+    pub fn reduce(&self) -> Self {
+        let free = self.free_reduce();
+        let handle = free.handle_reduce();
+        if handle.len() < free.len() {
+            handle
+        } else {
+            free
+        }
+    }
+
+    /// Returns the closure of the braid, joining the top of each strand to its bottom.
+    ///
+    /// Strands that never cross another strand become separate unknotted components.
+    pub fn closure(&self) -> Knot {
+        assert!(
+            self.strands <= usize::from(u8::MAX) + 1,
+            "braids on more than 256 strands are not supported"
+        );
+        // The diagram only has regions up to the rightmost strand that crosses anything, so
+        // every strand after that adds one region, which is untouched by any crossing.
+        let used = match self.word.iter().map(|g| g.unsigned_abs()).max() {
+            Some(g) => g + 1,
+            None => 1,
+        };
+        let mut knot = if self.word.is_empty() {
+            Knot {
+                crossings: Vec::new(),
+                region_num: 2,
+            }
+        } else {
+            Knot::from_crossing_builders(
+                self.word
+                    .iter()
+                    .map(|&g| {
+                        let orientation = if g > 0 {
+                            Orientation::Positive
+                        } else {
+                            Orientation::Negative
+                        };
+                        CrossingBuilder::new(g.unsigned_abs() as u8 - 1, orientation)
+                    })
+                    .collect(),
+            )
+        };
+        knot.region_num += self.strands.max(1) - used;
+        knot
+    }
+}
+
+/// Finds the handle that ends earliest in a freely reduced word, as the positions of its first
+/// and last letters.
+///
+/// Every letter between the ends of a `σi`-handle is above `σi`, so the letter that starts a
+/// handle ending at `σi^-e` is the closest earlier letter that is `σi` or below. Any handle
+/// inside another one ends earlier, so the handle found first contains no others.
+fn first_handle(word: &[isize]) -> Option<(usize, usize)> {
+    // The position of the last letter of each generator seen so far.
+    let mut last: Vec<Option<usize>> = Vec::new();
+    for (end, &g) in word.iter().enumerate() {
+        let i = g.unsigned_abs();
+        if last.len() <= i {
+            last.resize(i + 1, None);
+        }
+        if let Some(start) = last[1..=i].iter().flatten().max().cloned() {
+            if word[start] == -g {
+                return Some((start, end));
+            }
+        }
+        last[i] = Some(end);
+    }
+    None
+}
+
+impl From<&Braid> for Knot {
+    fn from(braid: &Braid) -> Self {
+        braid.closure()
+    }
+}
+
+impl From<Braid> for Knot {
+    fn from(braid: Braid) -> Self {
+        braid.closure()
+    }
+}
+
+impl<'a> Mul<&'a Braid> for &'a Braid {
+    type Output = Braid;
+
+    /// Composes two braids, putting `rhs` below `self`. The result has as many strands as the
+    /// larger of the two.
+    fn mul(self, rhs: &Braid) -> Braid {
+        let mut word = self.word.clone();
+        word.extend_from_slice(&rhs.word);
+        Braid::new(self.strands.max(rhs.strands), word)
+    }
+}
+
+impl Mul for Braid {
+    type Output = Braid;
+
+    fn mul(self, rhs: Braid) -> Braid {
+        &self * &rhs
+    }
+}
+
+impl FromStr for Braid {
+    type Err = KnotParseError;
+
+    /// Parses a braid in letter notation, on as few strands as the letters need.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_chars: Vec<char> = s.chars().filter(|c| !c.is_ascii_alphabetic()).collect();
+        if !bad_chars.is_empty() {
+            return Err(KnotParseError::InvalidCharacter(bad_chars));
+        }
+        let word: Vec<isize> = s
+            .chars()
+            .map(|c| {
+                let g = (c.to_ascii_lowercase() as u8 - b'a') as isize + 1;
+                if c.is_ascii_lowercase() {
+                    g
+                } else {
+                    -g
+                }
+            })
+            .collect();
+        let strands = word.iter().map(|g| g.unsigned_abs() + 1).max().unwrap_or(1);
+        Ok(Braid::new(strands, word))
+    }
+}
+
+impl fmt::Display for Braid {
+    /// Writes the braid in letter notation when every generator has a letter, and as a list of
+    /// signed generator indices otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.strands > 27 {
+            return write!(f, "{:?}", self.word);
+        }
+        for &g in &self.word {
+            let letter = (b'a' + g.unsigned_abs() as u8 - 1) as char;
+            let letter = if g > 0 {
+                letter
+            } else {
+                letter.to_ascii_uppercase()
+            };
+            write!(f, "{}", letter)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Braid;
+    use crate::{polynomial::Polynomial, BracketMethod, Knot};
+    use std::str::FromStr;
+
+    #[test]
+    fn algebra() {
+        let ab: Braid = "ab".parse().unwrap();
+        assert_eq!(ab.strands(), 3);
+        assert_eq!(ab.word(), &[1, 2]);
+        assert_eq!(ab.inverse().to_string(), "BA");
+        assert_eq!(ab.pow(3).to_string(), "ababab");
+        assert_eq!(ab.pow(-2).to_string(), "BABA");
+        assert!(ab.pow(0).is_empty());
+        assert_eq!(ab.mirror().to_string(), "AB");
+        assert_eq!(ab.reverse().to_string(), "ba");
+        assert_eq!((&ab * &ab.inverse()).len(), 4);
+        assert_eq!(
+            Braid::generator(2, 1) * Braid::new(4, vec![-3]),
+            "aC".parse().unwrap()
+        );
+        assert_eq!(Braid::full_twist(3).to_string(), "ababab");
+        assert_eq!(Braid::full_twist(3).exponent_sum(), 6);
+        assert!("a1".parse::<Braid>().is_err());
+    }
+
+    #[test]
+    fn reduction() {
+        let braid: Braid = "abBAcaAdD".parse().unwrap();
+        assert_eq!(braid.free_reduce().to_string(), "c");
+        assert_eq!(braid.free_reduce().strands(), 5);
+
+        assert_eq!(
+            "aBA".parse::<Braid>().unwrap().handle_reduce().to_string(),
+            "BAb"
+        );
+        let relations = [
+            "abaBAB",
+            "acAC",
+            "bcbCBC",
+            "abcabcabcabcaCBACBACBACBAA",
+            "abaBABabaBAB",
+            "BCBcbc",
+        ];
+        for word in &relations {
+            assert!(Braid::from_str(word).unwrap().is_trivial(), "{}", word);
+            assert!(Braid::from_str(word).unwrap().reduce().is_empty());
+        }
+        let full = Braid::full_twist(4);
+        assert!((&full * &full.reverse().inverse()).is_trivial());
+        for word in &["a", "abAB", "aBAb", "abcABC", "abbaBAAB", "cbaCBA"] {
+            let braid = Braid::from_str(word).unwrap();
+            assert!(!braid.is_trivial(), "{}", word);
+            let reduced = braid.handle_reduce();
+            // The lowest generator only appears with one sign.
+            let lowest = reduced.word().iter().map(|g| g.abs()).min().unwrap();
+            let signs: Vec<isize> = reduced
+                .word()
+                .iter()
+                .filter(|g| g.abs() == lowest)
+                .map(|g| g.signum())
+                .collect();
+            assert!(signs.windows(2).all(|w| w[0] == w[1]), "{}", reduced);
+            assert_eq!(
+                reduced.closure().jones_polynomial(),
+                braid.closure().jones_polynomial(),
+                "{}",
+                word
+            );
+        }
+
+        let redundant: Braid = "abABabAbaBAB".parse().unwrap();
+        assert!(redundant.reduce().len() < redundant.len());
+        assert_eq!(
+            redundant.reduce().closure().jones_polynomial(),
+            redundant.closure().jones_polynomial()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn generators() {
+        Braid::new(3, vec![3]);
+    }
+
+    #[test]
+    fn closure() {
+        for braid in &["a", "abAB", "aBcAbC", "ac", "bd", "aaCC"] {
+            assert_eq!(
+                Braid::from_str(braid).unwrap().closure().jones_polynomial(),
+                Knot::from_str(braid).unwrap().jones_polynomial(),
+                "{}",
+                braid
+            );
+        }
+
+        // A braid times its inverse closes up to an unlink, with one circle for each strand.
+        let loop_value: Polynomial = "-A^2 - A^-2".parse().unwrap();
+        for strands in 1..5 {
+            let unlink = loop_value.pow(strands as u32 - 1);
+            for &method in &[
+                BracketMethod::StateSum,
+                BracketMethod::Tait,
+                BracketMethod::Decomposition,
+            ] {
+                assert_eq!(
+                    Braid::identity(strands)
+                        .closure()
+                        .bracket_polynomial_with(method),
+                    unlink
+                );
+            }
+            let braid = Braid::new(strands, (1..strands as isize).collect());
+            assert_eq!(
+                (&braid * &braid.inverse()).closure().bracket_polynomial(),
+                unlink
+            );
+        }
+        // Strands that do not cross add unknotted components.
+        assert_eq!(
+            Braid::new(4, vec![1]).closure().bracket_polynomial(),
+            loop_value.pow(2) * Polynomial::from_str("-A^3").unwrap()
+        );
+
+        // Mirroring replaces `t` by `1/t` in the jones polynomial.
+        let knot = Braid::from_str("aaab").unwrap();
+        let jones = knot.closure().jones_polynomial();
+        let mirrored = knot.mirror().closure().jones_polynomial();
+        let inverted: Polynomial = "t^-1".parse().unwrap();
+        assert_eq!(jones.substitute(&inverted).unwrap(), mirrored);
+        assert_eq!(Knot::from(knot.reverse()).jones_polynomial(), jones);
+    }
+}
@@ -0,0 +1,301 @@
+//! An on-disk cache of computed invariants.
+//!
+//! Results are stored as small text files under a directory for the current version of the
+//! crate, so upgrading the crate starts a fresh cache rather than trusting results from older
+//! code. Each file is named after a hash of its key and invariant, and starts with the full key
+//! so that a hash collision is treated as a miss.
+
+use crate::{
+    polynomial::{Format, Polynomial},
+    Knot, Orientation,
+};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Numbers the temporary files written by this process, so that threads storing the same
+/// invariant at once do not write to the same file.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// The invariants that can be stored in an `InvariantCache`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invariant {
+    Bracket,
+    Beta,
+    Jones,
+}
+
+impl Invariant {
+    fn name(self) -> &'static str {
+        match self {
+            Invariant::Bracket => "bracket",
+            Invariant::Beta => "beta",
+            Invariant::Jones => "jones",
+        }
+    }
+
+    /// Computes the invariant of a knot.
+    pub fn compute(self, knot: &Knot) -> Polynomial {
+        match self {
+            Invariant::Bracket => knot.bracket_polynomial(),
+            Invariant::Beta => knot.beta_polynomial(),
+            Invariant::Jones => knot.jones_polynomial(),
+        }
+    }
+}
+
+/// A canonical description of a knot diagram, used to look up its invariants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    /// Creates the key for the closure of a braid.
+    ///
+    /// Rotating a braid word does not change its closure, so every rotation of the word gets
+    /// the same key.
+    ///
+    /// ```
+    /// use knot_solver::cache::CacheKey;
+    ///
+    /// assert_eq!(CacheKey::braid("bAc"), CacheKey::braid("cbA"));
+    /// ```
+    pub fn braid(braid: &str) -> Self {
+        let letters: Vec<char> = braid.chars().collect();
+        let smallest = (0..letters.len().max(1))
+            .map(|i| letters[i..].iter().chain(&letters[..i]).collect::<String>())
+            .min()
+            .unwrap();
+        CacheKey(format!("braid:{}", smallest))
+    }
+
+    /// Creates the key for a knot diagram, with its regions renumbered in the order they first
+    /// appear so that the numbering chosen when the diagram was built does not matter.
+    pub fn diagram(knot: &Knot) -> Self {
+        let mut number = vec![None; knot.num_regions()];
+        let mut next = 0;
+        let mut key = format!("diagram:{}", knot.num_regions());
+        for c in &knot.crossings {
+            key.push(';');
+            for &r in &[c.top, c.bottom, c.left, c.right] {
+                let n = *number[r].get_or_insert_with(|| {
+                    next += 1;
+                    next - 1
+                });
+                key.push_str(&format!("{},", n));
+            }
+            key.push(match c.orientation {
+                Orientation::Positive => '+',
+                Orientation::Negative => '-',
+            });
+        }
+        CacheKey(key)
+    }
+
+    /// Returns the key as text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A cache of invariants stored in a directory.
+pub struct InvariantCache {
+    dir: PathBuf,
+}
+
+impl InvariantCache {
+    /// Opens the cache in the given directory, creating it if needed. Results are kept in a
+    /// subdirectory for the current crate version.
+    pub fn open<P: AsRef<Path>>(root: P) -> io::Result<Self> {
+        let dir = root
+            .as_ref()
+            .join(format!("v{}", env!("CARGO_PKG_VERSION")));
+        fs::create_dir_all(&dir)?;
+        Ok(InvariantCache { dir })
+    }
+
+    /// Returns the default cache directory: `$KNOT_SOLVER_CACHE` if it is set, otherwise a
+    /// `knot-solver` directory in `$XDG_CACHE_HOME` or `$HOME/.cache`.
+    pub fn default_location() -> Option<PathBuf> {
+        if let Some(dir) = env::var_os("KNOT_SOLVER_CACHE") {
+            return Some(PathBuf::from(dir));
+        }
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map(|dir| dir.join("knot-solver"))
+    }
+
+    /// Returns the directory that results are stored in.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Looks up a stored invariant. Anything that cannot be read is treated as missing.
+    pub fn get(&self, key: &CacheKey, invariant: Invariant) -> Option<Polynomial> {
+        let contents = fs::read_to_string(self.file(key, invariant)).ok()?;
+        let mut lines = contents.lines();
+        if lines.next()? != key.as_str() {
+            return None;
+        }
+        let variable = lines.next()?.chars().next()?;
+        let polynomial: Polynomial = lines.next()?.parse().ok()?;
+        Some(polynomial.with_variable(variable))
+    }
+
+    /// Stores an invariant, replacing any earlier value.
+    ///
+    /// The file is written under a temporary name first and then renamed, so that other
+    /// threads and processes sharing the cache never see a partly written result.
+    pub fn insert(
+        &self,
+        key: &CacheKey,
+        invariant: Invariant,
+        polynomial: &Polynomial,
+    ) -> io::Result<()> {
+        let path = self.file(key, invariant);
+        let count = TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed);
+        let temporary = path.with_extension(format!("{}-{}.tmp", process::id(), count));
+        {
+            let mut file = fs::File::create(&temporary)?;
+            writeln!(file, "{}", key.as_str())?;
+            writeln!(file, "{}", polynomial.variable())?;
+            writeln!(file, "{}", polynomial.format(Format::Pretty))?;
+        }
+        fs::rename(temporary, path)
+    }
+
+    /// Returns the stored invariant, or computes and stores it if there is none. Failing to
+    /// store the result does not stop it being returned.
+    pub fn get_or_compute<F>(&self, key: &CacheKey, invariant: Invariant, compute: F) -> Polynomial
+    where
+        F: FnOnce() -> Polynomial,
+    {
+        self.get(key, invariant).unwrap_or_else(|| {
+            let polynomial = compute();
+            let _ = self.insert(key, invariant, &polynomial);
+            polynomial
+        })
+    }
+
+    /// Removes every stored result for the current crate version.
+    pub fn clear(&self) -> io::Result<()> {
+        fs::remove_dir_all(&self.dir)?;
+        fs::create_dir_all(&self.dir)
+    }
+
+    fn file(&self, key: &CacheKey, invariant: Invariant) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.{}", fnv1a(key.as_str()), invariant.name()))
+    }
+}
+
+/// The 64-bit FNV-1a hash, which is stable across platforms and releases of Rust.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a, CacheKey, Invariant, InvariantCache};
+    use crate::Knot;
+    use std::{env, fs, str::FromStr, thread};
+
+    #[test]
+    fn keys() {
+        assert_eq!(CacheKey::braid("abAB").as_str(), "braid:ABab");
+        assert_eq!(CacheKey::braid("aBaB"), CacheKey::braid("BaBa"));
+        assert_ne!(CacheKey::braid("aab"), CacheKey::braid("abb"));
+        assert_eq!(CacheKey::braid("").as_str(), "braid:");
+
+        let knot = Knot::from_str("aA").unwrap();
+        assert_eq!(
+            CacheKey::diagram(&knot),
+            CacheKey::diagram(&Knot::from_str("aA").unwrap())
+        );
+        assert_ne!(
+            CacheKey::diagram(&knot),
+            CacheKey::diagram(&Knot::from_str("aa").unwrap())
+        );
+
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn store() {
+        let root = env::temp_dir().join(format!("knot-solver-cache-test-{}", std::process::id()));
+        let cache = InvariantCache::open(&root).unwrap();
+        assert!(cache
+            .path()
+            .ends_with(format!("v{}", env!("CARGO_PKG_VERSION"))));
+
+        let knot = Knot::from_str("abAB").unwrap();
+        let key = CacheKey::braid("abAB");
+        assert_eq!(cache.get(&key, Invariant::Jones), None);
+
+        let jones = cache.get_or_compute(&key, Invariant::Jones, || knot.jones_polynomial());
+        assert_eq!(jones, knot.jones_polynomial());
+        // The stored value is used from now on, even under a rotated braid.
+        let stored = cache.get_or_compute(&CacheKey::braid("bABa"), Invariant::Jones, || {
+            panic!("not cached")
+        });
+        assert_eq!(stored, jones);
+        assert_eq!(stored.variable(), 't');
+        assert_eq!(cache.get(&key, Invariant::Bracket), None);
+
+        // Constant polynomials keep their variable.
+        let unknot = Knot::from_str("aB").unwrap();
+        let key = CacheKey::diagram(&unknot);
+        cache
+            .insert(&key, Invariant::Jones, &unknot.jones_polynomial())
+            .unwrap();
+        assert_eq!(
+            cache.get(&key, Invariant::Jones),
+            Some(unknot.jones_polynomial())
+        );
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(&key, Invariant::Jones), None);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn concurrent_inserts() {
+        let root =
+            env::temp_dir().join(format!("knot-solver-cache-threads-{}", std::process::id()));
+        let jones = Knot::from_str("aaa").unwrap().jones_polynomial();
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let root = root.clone();
+                let jones = jones.clone();
+                thread::spawn(move || {
+                    let cache = InvariantCache::open(&root).unwrap();
+                    for _ in 0..20 {
+                        cache
+                            .insert(&CacheKey::braid("aaa"), Invariant::Jones, &jones)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let cache = InvariantCache::open(&root).unwrap();
+        assert_eq!(
+            cache.get(&CacheKey::braid("aaa"), Invariant::Jones),
+            Some(jones)
+        );
+        // Every temporary file was renamed into place.
+        let files = fs::read_dir(cache.path()).unwrap().count();
+        assert_eq!(files, 1);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
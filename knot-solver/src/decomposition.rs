@@ -0,0 +1,286 @@
+use crate::{
+    options::Monitor,
+    polynomial::{Polynomial, Term},
+    ComputeError, Crossing, Knot,
+};
+use std::collections::HashMap;
+
+impl Knot {
+    /// Returns the width of the diagram for `BracketMethod::Decomposition`: the largest number
+    /// of regions that are only partly processed at any point while adding the crossings in
+    /// order. The running time of that method is exponential in the width.
+    pub fn decomposition_width(&self) -> usize {
+        self.crossing_order().1
+    }
+
+    /// Returns the order in which `decomposition_bracket` adds the crossings, along with the
+    /// width of that order.
+    fn crossing_order(&self) -> (Vec<usize>, usize) {
+        let mut remaining = vec![0; self.num_regions()];
+        for c in &self.crossings {
+            for r in regions(c) {
+                remaining[r] += 1;
+            }
+        }
+
+        let mut active = vec![false; self.num_regions()];
+        let mut active_count = 0;
+        let mut width = 0;
+        let mut done = vec![false; self.num_crossings()];
+        let mut order = Vec::with_capacity(self.num_crossings());
+        for _ in 0..self.num_crossings() {
+            // Greedily take the crossing that leaves the fewest regions partly processed.
+            let (next, after) = (0..self.num_crossings())
+                .filter(|&i| !done[i])
+                .map(|i| {
+                    let touched = regions(&self.crossings[i]);
+                    let opened = touched.iter().filter(|&&r| !active[r]).count();
+                    let closed = touched.iter().filter(|&&r| remaining[r] == 1).count();
+                    (i, active_count + opened - closed)
+                })
+                .min_by_key(|&(i, after)| (after, i))
+                .unwrap();
+
+            let touched = regions(&self.crossings[next]);
+            width = width.max(active_count + touched.iter().filter(|&&r| !active[r]).count());
+            for &r in &touched {
+                active[r] = true;
+            }
+            for &r in &touched {
+                remaining[r] -= 1;
+                if remaining[r] == 0 {
+                    active[r] = false;
+                }
+            }
+            active_count = after;
+            done[next] = true;
+            order.push(next);
+        }
+
+        (order, width)
+    }
+
+    /// Computes the bracket polynomial by adding the crossings one at a time while keeping track
+    /// of how the partly processed regions are connected.
+    ///
+    /// After each crossing, the states of the crossings added so far are grouped by which of the
+    /// regions that still touch other crossings they connect, so that the number of groups
+    /// depends on the width from `crossing_order` rather than on the number of crossings. Every
+    /// component that no longer has any such regions multiplies its state by `d = -A^2 - A^-2`
+    /// straight away, which keeps the coefficients as small as in the bracket itself. The
+    /// bracket counts one factor of `d` for each component after the first two, so the sum is
+    /// divided by `d^2` at the end. That division is exact because every diagram has at least
+    /// two more regions than crossings, which the constructors and deserialization make sure
+    /// of. The monitor is checked before each crossing.
+    pub(crate) fn decomposition_bracket(
+        &self,
+        monitor: &Monitor,
+    ) -> Result<Polynomial, ComputeError> {
+        let loop_value = Polynomial::from_vec(vec![Term::new(-1, 2), Term::new(-1, -2)]);
+        let (order, _) = self.crossing_order();
+
+        let mut last = vec![None; self.num_regions()];
+        for (step, &i) in order.iter().enumerate() {
+            for r in regions(&self.crossings[i]) {
+                last[r] = Some(step);
+            }
+        }
+
+        // Regions without a crossing are components of every state from the start.
+        let free = last.iter().filter(|l| l.is_none()).count();
+        let mut groups = HashMap::new();
+        groups.insert(Vec::new(), loop_value.pow(free as u32));
+
+        let mut frontier: Vec<usize> = Vec::new();
+        for (step, &i) in order.iter().enumerate() {
+            monitor.check()?;
+            let crossing = &self.crossings[i];
+            let opened: Vec<usize> = regions(crossing)
+                .into_iter()
+                .filter(|r| !frontier.contains(r))
+                .collect();
+            frontier.extend_from_slice(&opened);
+            let position = |r: usize| frontier.iter().position(|&f| f == r).unwrap();
+            let finished: Vec<usize> = (0..frontier.len())
+                .filter(|&p| last[frontier[p]] == Some(step))
+                .collect();
+
+            let mut next: HashMap<Vec<usize>, Polynomial> = HashMap::new();
+            for (boundary, weight) in groups {
+                for &bit in &[false, true] {
+                    let (u, v) = crossing.smoothing(bit);
+                    let mut labels = boundary.clone();
+                    let fresh = labels.len();
+                    labels.extend((0..opened.len()).map(|k| fresh + k));
+                    let (keep, merge) = (labels[position(u)], labels[position(v)]);
+                    for l in labels.iter_mut() {
+                        if *l == merge {
+                            *l = keep;
+                        }
+                    }
+
+                    let mut closed = 0;
+                    for &p in &finished {
+                        let shared = (0..labels.len())
+                            .any(|q| q != p && labels[q] == labels[p] && !finished.contains(&q));
+                        let earlier = finished
+                            .iter()
+                            .take_while(|&&q| q != p)
+                            .any(|&q| labels[q] == labels[p]);
+                        if !shared && !earlier {
+                            closed += 1;
+                        }
+                    }
+
+                    let kept: Vec<usize> = (0..labels.len())
+                        .filter(|p| !finished.contains(p))
+                        .map(|p| labels[p])
+                        .collect();
+                    let weight = weight.clone()
+                        * Term::new(1, if bit { -1 } else { 1 })
+                        * loop_value.pow(closed);
+
+                    *next
+                        .entry(normalize(&kept))
+                        .or_insert_with(Polynomial::zero) += weight;
+                }
+            }
+
+            groups = next;
+            frontier = (0..frontier.len())
+                .filter(|p| !finished.contains(p))
+                .map(|p| frontier[p])
+                .collect();
+            monitor.advance(1);
+        }
+
+        debug_assert!(groups.keys().all(Vec::is_empty));
+        let sum: Polynomial = groups.into_values().sum();
+        Ok(divide_by_loop(divide_by_loop(sum)))
+    }
+}
+
+/// Divides a polynomial by `-A^2 - A^-2`, which must divide it exactly.
+///
+/// # Panics
+///
+/// Panics if the polynomial is not divisible by `-A^2 - A^-2`.
+fn divide_by_loop(p: Polynomial) -> Polynomial {
+    // Dividing by `-A^2 - A^-2` is the same as multiplying by `-A^2` and dividing by `A^4 + 1`,
+    // which is done from the highest term down.
+    let divisor = Polynomial::from_vec(vec![Term::new(1, 4), Term::one()]);
+    let mut remainder = p * Term::new(-1, 2);
+    let lowest = remainder.iter().next().map(|t| t.exponent());
+    let mut quotient = Polynomial::zero();
+    loop {
+        let top = match remainder.iter().next_back() {
+            Some(&top) => top,
+            None => break,
+        };
+        let q = top * Term::new(1, -4);
+        assert!(
+            Some(q.exponent()) >= lowest,
+            "polynomial is not divisible by -A^2 - A^-2"
+        );
+        remainder += divisor.clone() * (q * -1);
+        quotient += q;
+    }
+    quotient
+}
+
+/// Renumbers labels in order of first appearance, so equal partitions have equal labels.
+fn normalize(labels: &[usize]) -> Vec<usize> {
+    let mut seen: Vec<usize> = Vec::new();
+    labels
+        .iter()
+        .map(|l| match seen.iter().position(|s| s == l) {
+            Some(i) => i,
+            None => {
+                seen.push(*l);
+                seen.len() - 1
+            }
+        })
+        .collect()
+}
+
+/// Returns the distinct regions around a crossing.
+fn regions(c: &Crossing) -> Vec<usize> {
+    let mut regions = vec![c.top, c.bottom, c.left, c.right];
+    regions.sort_unstable();
+    regions.dedup();
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{divide_by_loop, normalize};
+    use crate::polynomial::Polynomial;
+    use crate::{BracketMethod, Knot};
+    use std::str::FromStr;
+
+    #[test]
+    fn labels() {
+        assert_eq!(normalize(&[4, 2, 4, 7, 2]), vec![0, 1, 0, 2, 1]);
+        assert_eq!(normalize(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn division() {
+        let p: Polynomial = "-A^5 - A".parse().unwrap();
+        assert_eq!(divide_by_loop(p), "A^3".parse().unwrap());
+        let p: Polynomial = "A^4 + 2 + A^-4".parse().unwrap();
+        assert_eq!(divide_by_loop(p), "-A^2 - A^-2".parse().unwrap());
+    }
+
+    #[test]
+    fn bracket() {
+        for braid in &[
+            "a", "A", "aaa", "abAB", "aBcAbC", "abbaabb", "abcABC", "aBaBaB", "abcdabcd", "ac",
+            "c", "ace", "aCe", "aaCC", "e",
+        ] {
+            let knot = Knot::from_str(braid).unwrap();
+            assert_eq!(
+                knot.bracket_polynomial_with(BracketMethod::Decomposition),
+                knot.bracket_polynomial(),
+                "{}",
+                braid
+            );
+        }
+    }
+
+    #[test]
+    fn smallest_diagrams() {
+        // Diagrams with no crossings divide out the loops they start with.
+        let unknot = Knot::from_pd(&[]).unwrap();
+        let unlink = unknot.disjoint_union(&unknot);
+        for knot in &[&unknot, &unlink] {
+            for &method in &[BracketMethod::StateSum, BracketMethod::Tait] {
+                assert_eq!(
+                    knot.bracket_polynomial_with(BracketMethod::Decomposition),
+                    knot.bracket_polynomial_with(method)
+                );
+            }
+        }
+        assert_eq!(
+            unlink.bracket_polynomial_with(BracketMethod::Decomposition),
+            "-A^2 - A^-2".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn large_diagrams() {
+        // 120 crossings are far past what a state sum can handle, but these stay narrow.
+        let knot = Knot::from_str(&"a".repeat(120)).unwrap();
+        assert_eq!(knot.decomposition_width(), 5);
+        assert_eq!(
+            knot.bracket_polynomial_with(BracketMethod::Decomposition),
+            knot.bracket_polynomial_with(BracketMethod::Tait)
+        );
+
+        // The jones polynomial of a link with `c` components is `(-2)^(c - 1)` at `t = 1`.
+        let knot = Knot::from_str(&"abAB".repeat(30)).unwrap();
+        assert!(knot.decomposition_width() <= 8);
+        let jones = knot.jones_polynomial_with(BracketMethod::Decomposition);
+        assert_eq!(jones.eval(1), Ok(4));
+    }
+}
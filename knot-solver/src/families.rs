@@ -0,0 +1,241 @@
+//! Families of knots given by a few integers.
+//!
+//! Torus knots are closures of braids. Twist, pretzel and two-bridge knots are numerator
+//! closures of tangles, built up from single crossings as in Conway's notation. The numerator
+//! closure joins the northern ends of a tangle to each other and the southern ends to each
+//! other.
+//!
+//! - Twisting the eastern ends around each other `n` times adds `n` to the fraction of a
+//!   tangle, and twisting the southern ends around each other `n` times adds `n` to one over
+//!   its fraction.
+//! - The numerator closure of the tangle with fraction `p/q` is the two-bridge knot `b(p, q)`.
+//!   The tangle is made from the continued fraction of `p/q` by twisting alternately the
+//!   southern and eastern ends, finishing with the eastern ones.
+//! - The pretzel knot `P(a1, ..., ak)` is the numerator closure of columns of `a1, ..., ak`
+//!   crossings, each made by twisting the southern ends, put side by side.
+
+use crate::{braid::Braid, tangle::Tangle, Knot};
+
+impl Braid {
+    /// Creates the braid `(σ1 σ2 ... σ(p-1))^q` on `p` strands, whose closure is the `(p, q)`
+    /// torus knot. Negative `q` gives the mirror image.
+    pub fn torus(p: usize, q: i32) -> Self {
+        Braid::new(p, (1..p as isize).collect()).pow(q)
+    }
+}
+
+impl Knot {
+    /// Creates the `(p, q)` torus knot, which winds `p` times around the core of a torus and `q`
+    /// times around its hole, as the closure of `Braid::torus(p, q)`.
+    ///
+    /// It is a knot when `p` and `q` are coprime, and otherwise a link of `gcd(p, q)`
+    /// components.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// assert_eq!(
+    ///     Knot::torus(2, 3).jones_polynomial(),
+    ///     Knot::from_str("aaa").unwrap().jones_polynomial()
+    /// );
+    /// ```
+    pub fn torus(p: usize, q: i32) -> Knot {
+        Braid::torus(p, q).closure()
+    }
+
+    /// Creates the twist knot with `n` half-twists next to a clasp of two crossings, which is
+    /// the two-bridge knot with continued fraction `[2, n]`.
+    ///
+    /// `twist(1)` is the trefoil, `twist(2)` the figure-eight knot and `twist(0)` the unknot.
+    pub fn twist(n: isize) -> Knot {
+        Knot::rational(&[2, n])
+    }
+
+    /// Creates the pretzel knot with columns of the given numbers of crossings side by side,
+    /// with negative numbers for columns twisted the other way.
+    ///
+    /// ```
+    /// use knot_solver::{Chirality, Knot};
+    ///
+    /// let pretzel = Knot::pretzel(&[-2, 3, 7]);
+    /// assert_eq!(pretzel.num_crossings(), 12);
+    /// assert_eq!(pretzel.chirality_hint(), Chirality::Chiral);
+    /// ```
+    pub fn pretzel(twists: &[isize]) -> Knot {
+        twists
+            .iter()
+            .map(|&n| Tangle::infinity().twist_southern(n))
+            .fold(Tangle::zero(), Tangle::add)
+            .numerator()
+    }
+
+    /// Creates the two-bridge knot `b(p, q)`, the numerator closure of the rational tangle
+    /// with fraction `p/q`. It is a knot when `p` is odd and a link of two components when `p`
+    /// is even.
+    ///
+    /// `b(p, q)` and `b(p, q')` are the same when `q q' = 1` modulo `p`, and mirror images of
+    /// each other when `q q' = -1`.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// assert_eq!(
+    ///     Knot::two_bridge(5, 2).jones_polynomial(),
+    ///     Knot::from_str("aBaB").unwrap().jones_polynomial()
+    /// );
+    /// ```
+    pub fn two_bridge(p: usize, q: isize) -> Knot {
+        let (mut p, mut q, sign) = (p as isize, q.abs(), q.signum());
+        let mut continued_fraction = Vec::new();
+        while q != 0 {
+            continued_fraction.push(sign * (p / q));
+            let r = p % q;
+            p = q;
+            q = r;
+        }
+        Knot::rational(&continued_fraction)
+    }
+
+    /// Creates the rational knot whose tangle has the fraction
+    /// `a1 + 1/(a2 + 1/(... + 1/ak))` for the continued fraction `[a1, a2, ..., ak]`.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    ///
+    /// assert_eq!(
+    ///     Knot::rational(&[2, 1, 1]).jones_polynomial(),
+    ///     Knot::two_bridge(5, 2).jones_polynomial()
+    /// );
+    /// ```
+    pub fn rational(continued_fraction: &[isize]) -> Knot {
+        // The innermost part of the fraction is made first, and the twists of the first entry
+        // are always eastern.
+        let start = if continued_fraction.len() % 2 == 1 {
+            Tangle::zero()
+        } else {
+            Tangle::infinity()
+        };
+        continued_fraction
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(start, |tangle, (i, &n)| {
+                if i % 2 == 0 {
+                    tangle.twist_eastern(n)
+                } else {
+                    tangle.twist_southern(n)
+                }
+            })
+            .numerator()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Chirality, Knot};
+    use std::str::FromStr;
+
+    fn jones(word: &str) -> crate::polynomial::Polynomial {
+        Knot::from_str(word).unwrap().jones_polynomial()
+    }
+
+    #[test]
+    fn torus_knots() {
+        assert_eq!(Knot::torus(2, 5).jones_polynomial(), jones("aaaaa"));
+        assert_eq!(Knot::torus(2, -3).jones_polynomial(), jones("AAA"));
+        assert_eq!(
+            Knot::torus(3, 2).jones_polynomial(),
+            Knot::torus(2, 3).jones_polynomial()
+        );
+        assert_eq!(
+            Knot::torus(3, 4).jones_polynomial(),
+            Knot::torus(4, 3).jones_polynomial()
+        );
+        assert_eq!(Knot::torus(3, 4).num_crossings(), 8);
+        assert_eq!(Knot::torus(1, 5).num_regions(), 2);
+    }
+
+    #[test]
+    fn rational_knots() {
+        let unknot = Knot::from_pd(&[]).unwrap().jones_polynomial();
+        assert_eq!(Knot::two_bridge(3, 1).jones_polynomial(), jones("aaa"));
+        assert_eq!(
+            Knot::two_bridge(2, 1).bracket_polynomial(),
+            Knot::from_str("aa").unwrap().bracket_polynomial()
+        );
+        assert_eq!(Knot::two_bridge(1, 3).jones_polynomial(), unknot);
+        assert_eq!(Knot::two_bridge(5, 2).jones_polynomial(), jones("aBaB"));
+        assert_eq!(Knot::two_bridge(0, 1).num_regions(), 3);
+
+        // `2 * 4 = 1` and `2 * 3 = -1` modulo 7.
+        let five_two = Knot::two_bridge(7, 2);
+        assert_eq!(five_two.num_crossings(), 5);
+        assert_eq!(
+            Knot::two_bridge(7, 4).jones_polynomial(),
+            five_two.jones_polynomial()
+        );
+        assert_eq!(
+            Knot::two_bridge(7, 3).jones_polynomial(),
+            five_two.mirror().jones_polynomial()
+        );
+        assert_eq!(
+            Knot::two_bridge(7, -2).jones_polynomial(),
+            five_two.mirror().jones_polynomial()
+        );
+        assert_eq!(
+            Knot::rational(&[3, 2]).jones_polynomial(),
+            five_two.jones_polynomial()
+        );
+
+        // Links need every component traced the same way through all of its crossings.
+        let hopf = Knot::from_str("aa").unwrap();
+        for link in &[
+            Knot::two_bridge(2, 1),
+            Knot::rational(&[2]),
+            Knot::pretzel(&[1, 1]),
+        ] {
+            assert_eq!(link.writhe(), 2);
+            assert_eq!(link.jones_polynomial(), hopf.jones_polynomial());
+        }
+        let torus = Knot::two_bridge(4, 1);
+        assert_eq!(torus.writhe(), 4);
+        assert_eq!(torus.jones_polynomial(), jones("aaaa"));
+        let whitehead: crate::polynomial::Polynomial =
+            "t^(7/2) - 2t^(5/2) + t^(3/2) - 2t^(1/2) + t^(-1/2) - t^(-3/2)"
+                .parse()
+                .unwrap();
+        assert_eq!(Knot::two_bridge(8, 3).jones_polynomial(), whitehead);
+        assert_eq!(
+            Knot::pretzel(&[2, -2]).jones_polynomial(),
+            "-t^(1/2) - t^(-1/2)".parse().unwrap()
+        );
+
+        assert_eq!(Knot::twist(0).jones_polynomial(), unknot);
+        assert_eq!(Knot::twist(1).jones_polynomial(), jones("aaa"));
+        assert_eq!(Knot::twist(2).jones_polynomial(), jones("aBaB"));
+        assert_eq!(
+            Knot::twist(3).jones_polynomial(),
+            Knot::two_bridge(7, 3).jones_polynomial()
+        );
+        assert_eq!(Knot::twist(4).chirality_hint(), Chirality::Chiral);
+    }
+
+    #[test]
+    fn pretzel_knots() {
+        assert_eq!(Knot::pretzel(&[1, 1, 1]).jones_polynomial(), jones("aaa"));
+        assert_eq!(Knot::pretzel(&[3, 2]).jones_polynomial(), jones("AAAAA"));
+        // A column of one crossing is also a row of one crossing.
+        assert_eq!(
+            Knot::pretzel(&[2, 1]).jones_polynomial(),
+            Knot::two_bridge(3, 2).jones_polynomial()
+        );
+        assert_eq!(Knot::pretzel(&[3, -3, 3]).num_crossings(), 9);
+        assert_eq!(Knot::pretzel(&[]).num_regions(), 3);
+        assert_eq!(
+            Knot::pretzel(&[-2, 3, 7]).chirality_hint(),
+            Chirality::Chiral
+        );
+    }
+}
@@ -0,0 +1,550 @@
+//! The Garside structure of the braid groups: left normal forms and super summit sets.
+//!
+//! Every braid on `n` strands can be written uniquely as `Δ^p A1 A2 ... Ak`, where `Δ` is the
+//! half twist and each `Ai` is a simple braid, a positive braid in which every pair of strands
+//! crosses at most once, other than the identity and `Δ`. Consecutive factors are left-weighted:
+//! every generator that `A(i+1)` can start with is one that `Ai` can end with, so no crossing can
+//! be moved to an earlier factor. This is the left normal form, and it decides whether two words
+//! give the same braid.
+//!
+//! A simple braid is determined by the permutation it makes of the strands, so simple braids are
+//! stored as permutations, with entry `j` giving the final position of the strand that starts at
+//! position `j`.
+
+use crate::braid::Braid;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+};
+
+/// A simple braid, stored as its permutation of the strands.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Simple(Vec<usize>);
+
+impl Simple {
+    fn identity(n: usize) -> Self {
+        Simple((0..n).collect())
+    }
+
+    /// The half twist, in which every pair of strands crosses once.
+    fn delta(n: usize) -> Self {
+        Simple((0..n).rev().collect())
+    }
+
+    fn is_identity(&self) -> bool {
+        self.0.iter().enumerate().all(|(j, &p)| j == p)
+    }
+
+    fn is_delta(&self) -> bool {
+        let n = self.0.len();
+        self.0.iter().enumerate().all(|(j, &p)| p == n - 1 - j)
+    }
+
+    fn inverse_permutation(&self) -> Vec<usize> {
+        let mut inverse = vec![0; self.0.len()];
+        for (j, &p) in self.0.iter().enumerate() {
+            inverse[p] = j;
+        }
+        inverse
+    }
+
+    /// Whether the braid can start with `σ(i+1)`, which is when the strands starting at `i` and
+    /// `i + 1` cross.
+    fn starts_with(&self, i: usize) -> bool {
+        self.0[i] > self.0[i + 1]
+    }
+
+    /// Whether the braid can end with `σ(i+1)`, which is when the strands ending at `i` and
+    /// `i + 1` have crossed.
+    fn ends_with(&self, i: usize, inverse: &[usize]) -> bool {
+        inverse[i] > inverse[i + 1]
+    }
+
+    /// Conjugation by `Δ`, which turns each `σi` into `σ(n-i)`.
+    fn flip(&self) -> Self {
+        let n = self.0.len();
+        Simple((0..n).map(|j| n - 1 - self.0[n - 1 - j]).collect())
+    }
+
+    /// Applies `flip` `times` times.
+    fn flip_by(&self, times: isize) -> Self {
+        if times % 2 == 0 {
+            self.clone()
+        } else {
+            self.flip()
+        }
+    }
+
+    /// The simple braid `B` with `self B = Δ`.
+    fn complement(&self) -> Self {
+        let n = self.0.len();
+        Simple(
+            self.inverse_permutation()
+                .iter()
+                .map(|&j| n - 1 - j)
+                .collect(),
+        )
+    }
+
+    /// The product `self other`, which must be simple.
+    fn then(&self, other: &Simple) -> Self {
+        Simple(self.0.iter().map(|&p| other.0[p]).collect())
+    }
+
+    /// The simple braid `B` with `self B = other`, where `self` must be a prefix of `other`.
+    fn left_divide(&self, other: &Simple) -> Self {
+        Simple(
+            self.inverse_permutation()
+                .iter()
+                .map(|&j| other.0[j])
+                .collect(),
+        )
+    }
+
+    /// Whether the strands starting at `i < j` cross.
+    fn crosses(&self, i: usize, j: usize) -> bool {
+        self.0[i] > self.0[j]
+    }
+
+    /// The least common multiple of two simple braids, which is simple. Its crossings are those
+    /// of either braid, along with any that they force: if `i` crosses `j` and `j` crosses `k`
+    /// for `i < j < k`, then `i` has to cross `k`.
+    fn join(&self, other: &Simple) -> Self {
+        let n = self.0.len();
+        let mut crossed: Vec<Vec<bool>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| i < j && (self.crosses(i, j) || other.crosses(i, j)))
+                    .collect()
+            })
+            .collect();
+        loop {
+            let mut changed = false;
+            for i in 0..n {
+                for j in i + 1..n {
+                    for k in j + 1..n {
+                        if crossed[i][j] && crossed[j][k] && !crossed[i][k] {
+                            crossed[i][k] = true;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        Simple(
+            (0..n)
+                .map(|i| {
+                    (0..i).filter(|&j| !crossed[j][i]).count()
+                        + (i + 1..n).filter(|&j| crossed[i][j]).count()
+                })
+                .collect(),
+        )
+    }
+
+    /// Writes the simple braid as a positive word, taking the lowest generator it can start with
+    /// each time.
+    fn word(&self) -> Vec<isize> {
+        let mut word = Vec::new();
+        let mut rest = self.0.clone();
+        while let Some(i) = (0..rest.len().saturating_sub(1)).find(|&i| rest[i] > rest[i + 1]) {
+            word.push(i as isize + 1);
+            rest.swap(i, i + 1);
+        }
+        word
+    }
+}
+
+/// Moves crossings from `b` to `a` until the pair is left-weighted, returning whether anything
+/// moved.
+fn left_weight(a: &mut Simple, b: &mut Simple) -> bool {
+    let mut changed = false;
+    loop {
+        let inverse = a.inverse_permutation();
+        let i = match (0..a.0.len() - 1).find(|&i| b.starts_with(i) && !a.ends_with(i, &inverse)) {
+            Some(i) => i,
+            None => return changed,
+        };
+        // `a σi` and `σi^-1 b` are both still simple.
+        let (x, y) = (inverse[i], inverse[i + 1]);
+        a.0.swap(x, y);
+        b.0.swap(i, i + 1);
+        changed = true;
+    }
+}
+
+/// The left normal form `Δ^p A1 ... Ak` of a braid, created by `Braid::left_normal_form`.
+///
+/// ```
+/// use knot_solver::braid::Braid;
+///
+/// let braid: Braid = "abA".parse().unwrap();
+/// let normal = braid.left_normal_form();
+/// assert_eq!((normal.inf(), normal.sup()), (-1, 1));
+/// assert_eq!(normal, "Bab".parse::<Braid>().unwrap().left_normal_form());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NormalForm {
+    strands: usize,
+    delta: isize,
+    factors: Vec<Simple>,
+}
+
+impl NormalForm {
+    /// Normalises `Δ^delta` times a product of simple braids.
+    fn new(strands: usize, mut delta: isize, mut factors: Vec<Simple>) -> Self {
+        loop {
+            let mut changed = false;
+            for j in (1..factors.len()).rev() {
+                let (left, right) = factors.split_at_mut(j);
+                changed |= left_weight(&mut left[j - 1], &mut right[0]);
+            }
+            if !changed {
+                break;
+            }
+        }
+        // Copies of `Δ` can only be at the start, and identities at the end.
+        let twists = factors.iter().take_while(|f| f.is_delta()).count();
+        delta += twists as isize;
+        factors.drain(..twists);
+        while factors.last().is_some_and(Simple::is_identity) {
+            factors.pop();
+        }
+        NormalForm {
+            strands,
+            delta,
+            factors,
+        }
+    }
+
+    /// Returns the number of strands.
+    pub fn strands(&self) -> usize {
+        self.strands
+    }
+
+    /// Returns the power of `Δ` at the start of the normal form.
+    pub fn inf(&self) -> isize {
+        self.delta
+    }
+
+    /// Returns the power of `Δ` plus the number of other factors.
+    pub fn sup(&self) -> isize {
+        self.delta + self.factors.len() as isize
+    }
+
+    /// Returns the number of factors other than `Δ`.
+    pub fn canonical_length(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// Returns the braid the normal form describes, as a word with the power of `Δ` first.
+    pub fn to_braid(&self) -> Braid {
+        let mut word = Braid::half_twist(self.strands)
+            .pow(self.delta as i32)
+            .word()
+            .to_vec();
+        for f in &self.factors {
+            word.extend(f.word());
+        }
+        Braid::new(self.strands, word)
+    }
+
+    /// Conjugates by the first factor, moving it to the end.
+    fn cycle(&self) -> Self {
+        match self.factors.split_first() {
+            Some((first, rest)) => {
+                let mut factors = rest.to_vec();
+                factors.push(first.flip_by(self.delta));
+                NormalForm::new(self.strands, self.delta, factors)
+            }
+            None => self.clone(),
+        }
+    }
+
+    /// Conjugates by the inverse of the last factor, moving it to the start.
+    fn decycle(&self) -> Self {
+        match self.factors.split_last() {
+            Some((last, rest)) => {
+                let mut factors = vec![last.flip_by(self.delta)];
+                factors.extend_from_slice(rest);
+                NormalForm::new(self.strands, self.delta, factors)
+            }
+            None => self.clone(),
+        }
+    }
+
+    /// Returns `c^-1 self c` for a simple braid `c`.
+    fn conjugate(&self, c: &Simple) -> Self {
+        // `c^-1 = Δ^-1 flip(∂c)`, and moving `Δ^delta` to the front flips it `delta` more times.
+        let mut factors = vec![c.complement().flip_by(self.delta + 1)];
+        factors.extend_from_slice(&self.factors);
+        factors.push(c.clone());
+        NormalForm::new(self.strands, self.delta - 1, factors)
+    }
+
+    fn inverse(&self) -> Self {
+        self.to_braid().inverse().left_normal_form()
+    }
+
+    /// Returns the smallest simple braid `e` such that `self c e` starts with `flip^delta(c)`,
+    /// which is the identity exactly when conjugating by `c` does not lower the infimum. Every
+    /// simple braid `d` that starts with `c` and does not lower the infimum starts with `c e`.
+    fn infimum_complement(&self, c: &Simple) -> Simple {
+        let mut factors = self.factors.clone();
+        factors.push(c.clone());
+        let product = NormalForm::new(self.strands, 0, factors);
+        let mut target = c.flip_by(self.delta);
+        if product.delta > 0 {
+            return Simple::identity(self.strands);
+        }
+        for f in &product.factors {
+            target = f.left_divide(&f.join(&target));
+        }
+        target
+    }
+
+    /// Returns the smallest simple braid starting with `s` that conjugates `self`, which is in
+    /// its super summit set, to another element of the set.
+    fn minimal_conjugator(&self, inverse: &NormalForm, s: Simple) -> Simple {
+        // The supremum of a conjugate is minus the infimum of its inverse.
+        let mut c = s;
+        loop {
+            let e = self.infimum_complement(&c);
+            let e = if e.is_identity() {
+                inverse.infimum_complement(&c)
+            } else {
+                e
+            };
+            if e.is_identity() {
+                return c;
+            }
+            c = c.then(&e);
+        }
+    }
+
+    /// Returns a conjugate with the largest infimum and smallest supremum in its conjugacy class,
+    /// found by cycling and decycling.
+    fn summit(&self) -> Self {
+        let tries = self.strands * self.strands.saturating_sub(1) / 2;
+        let mut x = self.clone();
+        'cycling: loop {
+            let mut y = x.clone();
+            for _ in 0..tries {
+                y = y.cycle();
+                if y.inf() > x.inf() {
+                    x = y;
+                    continue 'cycling;
+                }
+            }
+            break;
+        }
+        'decycling: loop {
+            let mut y = x.clone();
+            for _ in 0..tries {
+                y = y.decycle();
+                if y.sup() < x.sup() {
+                    x = y;
+                    continue 'decycling;
+                }
+            }
+            break;
+        }
+        x
+    }
+
+    /// Returns the super summit set of the braid: its conjugates with the largest infimum and
+    /// smallest supremum, in order.
+    pub fn super_summit_set(&self) -> Vec<NormalForm> {
+        let start = self.summit();
+        let mut set = BTreeSet::new();
+        set.insert(start.clone());
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(x) = queue.pop_front() {
+            let inverse = x.inverse();
+            for i in 0..self.strands.saturating_sub(1) {
+                let mut atom = Simple::identity(self.strands);
+                atom.0.swap(i, i + 1);
+                let c = x.minimal_conjugator(&inverse, atom);
+                let y = x.conjugate(&c);
+                if set.insert(y.clone()) {
+                    queue.push_back(y);
+                }
+            }
+        }
+        set.into_iter().collect()
+    }
+}
+
+impl fmt::Display for NormalForm {
+    /// Writes the normal form as the power of `Δ` followed by each factor in letter notation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Δ^{}", self.delta)?;
+        for factor in &self.factors {
+            write!(f, " {}", Braid::new(self.strands, factor.word()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Braid {
+    /// Creates the half twist `Δ`, the positive braid in which every pair of strands crosses
+    /// once.
+    pub fn half_twist(strands: usize) -> Self {
+        Braid::new(strands, Simple::delta(strands).word())
+    }
+
+    /// Returns the left normal form of the braid.
+    pub fn left_normal_form(&self) -> NormalForm {
+        let n = self.strands();
+        let negatives = self.word().iter().filter(|&&g| g < 0).count() as isize;
+        // Each `σi^-1` is written as `Δ^-1 (Δ σi^-1)`, and every `Δ^-1` is moved to the front,
+        // flipping the factors it passes.
+        let mut seen = 0;
+        let mut factors = Vec::with_capacity(self.len());
+        for &g in self.word() {
+            let i = g.unsigned_abs() - 1;
+            let factor = if g > 0 {
+                let mut f = Simple::identity(n);
+                f.0.swap(i, i + 1);
+                f
+            } else {
+                seen += 1;
+                let mut f = Simple::delta(n);
+                let (x, y) = (
+                    f.0.iter().position(|&p| p == i),
+                    f.0.iter().position(|&p| p == i + 1),
+                );
+                f.0.swap(x.unwrap(), y.unwrap());
+                f
+            };
+            factors.push(factor.flip_by(negatives - seen));
+        }
+        NormalForm::new(n, -negatives, factors)
+    }
+
+    /// Returns whether two braids on the same number of strands are equal.
+    pub fn is_equivalent(&self, other: &Braid) -> bool {
+        self.strands() == other.strands() && self.left_normal_form() == other.left_normal_form()
+    }
+
+    /// Returns the super summit set of the braid, the conjugates with the largest infimum and
+    /// smallest supremum. It is the same for every braid in a conjugacy class.
+    pub fn super_summit_set(&self) -> Vec<NormalForm> {
+        self.left_normal_form().super_summit_set()
+    }
+
+    /// Returns the smallest element of the super summit set, which is the same for exactly the
+    /// braids that are conjugate to this one. Conjugate braids have the same closure, so this
+    /// can be used to find repeated inputs.
+    pub fn conjugacy_representative(&self) -> NormalForm {
+        self.super_summit_set()
+            .into_iter()
+            .next()
+            .expect("the super summit set is never empty")
+    }
+
+    /// Returns whether two braids on the same number of strands are conjugate.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// let a: Braid = "aabAB".parse().unwrap();
+    /// let b: Braid = "abABa".parse().unwrap();
+    /// assert!(a.is_conjugate(&b));
+    /// assert!(!a.is_conjugate(&"aaaBB".parse().unwrap()));
+    /// ```
+    pub fn is_conjugate(&self, other: &Braid) -> bool {
+        self.strands() == other.strands()
+            && self.exponent_sum() == other.exponent_sum()
+            && self.conjugacy_representative() == other.conjugacy_representative()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Simple;
+    use crate::braid::Braid;
+    use std::str::FromStr;
+
+    fn braid(s: &str) -> Braid {
+        Braid::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn simples() {
+        let delta = Simple::delta(4);
+        assert_eq!(delta.word().len(), 6);
+        assert_eq!(delta.flip(), delta);
+        assert!(delta.complement().is_identity());
+
+        let a = Simple(vec![1, 0, 2]);
+        let b = Simple(vec![0, 2, 1]);
+        // The least common multiple of `σ1` and `σ2` is `Δ`.
+        assert!(a.join(&b).is_delta());
+        assert_eq!(a.join(&a), a);
+        assert_eq!(a.flip(), b);
+        assert_eq!(a.then(&a.complement()), Simple::delta(3));
+        assert_eq!(a.left_divide(&Simple::delta(3)), a.complement());
+    }
+
+    #[test]
+    fn normal_forms() {
+        assert_eq!(braid("aba").left_normal_form().to_string(), "Δ^1");
+        assert_eq!(Braid::half_twist(3), braid("aba"));
+        assert_eq!(braid("ab").left_normal_form().to_string(), "Δ^0 ab");
+        assert_eq!(braid("abab").left_normal_form().to_string(), "Δ^1 b");
+        assert_eq!(braid("A").left_normal_form().to_string(), "Δ^-1");
+        assert_eq!(
+            Braid::new(3, vec![-1]).left_normal_form().to_string(),
+            "Δ^-1 ab"
+        );
+        assert!(braid("abaBAB").left_normal_form().to_braid().is_empty());
+        assert!(braid("bcbCBC").is_equivalent(&Braid::identity(4)));
+        assert!(braid("ac").is_equivalent(&braid("ca")));
+        assert!(!braid("ab").is_equivalent(&braid("ba")));
+
+        for word in &["aBcAbC", "abbaBAAB", "AAbcBa", "cbaCBAabc", "aaBBaCC"] {
+            let braid = braid(word);
+            let normal = braid.left_normal_form();
+            assert_eq!(normal.to_braid().left_normal_form(), normal, "{}", word);
+            assert!(normal.inf() <= normal.sup());
+            // Handle reduction decides triviality without using normal forms.
+            assert!((&normal.to_braid() * &braid.inverse()).is_trivial());
+            assert_eq!(
+                braid.is_trivial(),
+                normal.inf() == 0 && normal.canonical_length() == 0
+            );
+        }
+    }
+
+    #[test]
+    fn conjugacy() {
+        let x = Braid::new(4, vec![1, 1, 2, -1, -2, 3]);
+        for conjugator in &["a", "b", "Ab", "abAAc", "cc"] {
+            let c = braid(conjugator);
+            let y = &(&c.inverse() * &x) * &c;
+            assert!(x.is_conjugate(&y), "{}", conjugator);
+        }
+
+        // The same exponent sum, but closures with different numbers of components.
+        assert!(!braid("aabb").is_conjugate(&braid("abab")));
+        assert!(braid("aaab").is_conjugate(&braid("abbb")));
+        // `σ1` and `σ2` are conjugate by `Δ`.
+        assert!(Braid::new(3, vec![1]).is_conjugate(&Braid::new(3, vec![2])));
+        assert!(!Braid::new(3, vec![1]).is_conjugate(&Braid::new(3, vec![-2])));
+        assert!(!braid("a").is_conjugate(&braid("b")));
+
+        let set = braid("abAB").super_summit_set();
+        assert!(set
+            .iter()
+            .all(|x| x.inf() == set[0].inf() && x.sup() == set[0].sup()));
+        assert!(set.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(
+            braid("abAB").conjugacy_representative(),
+            braid("BAba").conjugacy_representative()
+        );
+    }
+}
@@ -1,16 +1,62 @@
-use crate::polynomial::{Binomial, Polynomial, Term};
+use crate::polynomial::{Polynomial, Term};
 use bitvec::{BitVec, LittleEndian};
-use std::{collections::HashSet, str::FromStr};
+use num::rational::Rational;
+#[cfg(feature = "rayon")]
 use rayon::prelude::*;
-
+use std::{cmp::Ordering, convert::TryFrom, ops::Range, str::FromStr};
+
+pub mod braid;
+pub mod cache;
+mod decomposition;
+mod families;
+mod garside;
+mod markov;
+mod options;
+mod partial;
+mod pd;
 pub mod polynomial;
+#[cfg(feature = "rand")]
+pub mod random;
+mod reidemeister;
+mod satellite;
+#[cfg(feature = "serde")]
+mod serialization;
+mod states;
+mod sum;
+mod symmetry;
+mod tait;
+mod tangle;
+
+use crate::options::Monitor;
+pub use crate::options::{CancellationToken, ComputeError, ComputeOptions, Progress};
+pub use crate::partial::{MergeError, PartialBracket};
+pub use crate::satellite::Clasp;
+use crate::states::GrayWalker;
+pub use crate::states::{Enumeration, StateHistogram};
+pub use crate::symmetry::Chirality;
+pub use crate::tait::{EdgeList, TaitEdge, TaitGraph};
+
+/// The number of states that are processed together, whether walked in Gray-code order or
+/// checked against `ComputeOptions` between chunks.
+const CHUNK_SIZE: u128 = 1 << 12;
 
 /// Represents a knot.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialization::KnotData")
+)]
 pub struct Knot {
     crossings: Vec<Crossing>,
+    #[cfg_attr(feature = "serde", serde(rename = "regions"))]
     region_num: usize,
 }
 
+/// A link of any number of components. A `Knot` can already have more than one component, so
+/// this is the same type, named for code that works with links.
+pub type Link = Knot;
+
 impl Knot {
     /// Returns the number of regions in the unresolved knot.
     pub fn num_regions(&self) -> usize {
@@ -24,40 +70,229 @@ impl Knot {
 
     /// Iterates over all possible resolutions of the knot, returning a `Vec<(usize, i16)>` containing the
     /// number of unknots in each and the difference between the number of 0 and infinity resolutions taken.
+    ///
+    /// The result has one entry for each of the `2^n` states, so this is only practical for small
+    /// diagrams. Use `states` or `fold_states` to process the states without storing them.
     pub fn resolutions(&self) -> Vec<(usize, i16)> {
-        let r = (0u128..(2u128.pow(self.num_crossings() as u32))).into_par_iter();
-        r.map(|n| {
-            let mut diff: i16 = 0;
-            let bits: BitVec<LittleEndian, _> = BitVec::from(&n.to_le_bytes()[..]);
-            let mut counter = RegionCounter::new(self.num_regions());
-            self.crossings
-                .iter()
-                .zip(bits.iter())
-                .for_each(|(crossing, bit)| {
-                    if bit {
-                        diff -= 1; // TODO: might need to switch
-                        match crossing.orientation {
-                            Orientation::Positive => counter.combine(crossing.left, crossing.right),
-                            Orientation::Negative => counter.combine(crossing.top, crossing.bottom),
-                        }
-                    } else {
-                        diff += 1; // TODO: might need to switch
-                        match crossing.orientation {
-                            Orientation::Positive => counter.combine(crossing.top, crossing.bottom),
-                            Orientation::Negative => counter.combine(crossing.left, crossing.right),
-                        }
-                    }
-                });
-            (counter.current_count() - 1, diff)
-        })
-        .collect()
+        self.try_resolutions(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Like `resolutions`, but visits the states using the given strategy. The states are
+    /// returned in the order they were visited, so `Enumeration::GrayCode` lists the state with
+    /// index `i ^ (i >> 1)` at position `i`.
+    pub fn resolutions_with(&self, enumeration: Enumeration) -> Vec<(usize, i16)> {
+        self.states(enumeration).collect()
+    }
+
+    /// Returns an iterator over the number of unknots and the smoothing difference of every
+    /// state, visited using the given strategy.
+    pub fn states(&self, enumeration: Enumeration) -> impl Iterator<Item = (usize, i16)> + '_ {
+        self.range_states(enumeration, 0..2u128.pow(self.num_crossings() as u32))
+    }
+
+    /// Returns a parallel iterator over the number of unknots and the smoothing difference of
+    /// every state, visited using the given strategy. It runs in the thread pool it is used in.
+    #[cfg(feature = "rayon")]
+    pub fn par_states(
+        &self,
+        enumeration: Enumeration,
+    ) -> impl ParallelIterator<Item = (usize, i16)> + '_ {
+        let total = 2u128.pow(self.num_crossings() as u32);
+        // Split the order into contiguous chunks that are each walked incrementally.
+        let size = total.min(CHUNK_SIZE);
+        (0..total / size)
+            .into_par_iter()
+            .flat_map_iter(move |k| self.range_states(enumeration, k * size..(k + 1) * size))
+    }
+
+    /// Returns an iterator over the states at the given positions of the order the strategy
+    /// visits them in.
+    fn range_states(
+        &self,
+        enumeration: Enumeration,
+        positions: Range<u128>,
+    ) -> Box<dyn Iterator<Item = (usize, i16)> + '_> {
+        match enumeration {
+            Enumeration::Direct => {
+                let mut counter = RegionCounter::new(self.num_regions());
+                Box::new(positions.map(move |n| self.state(&mut counter, n)))
+            }
+            Enumeration::GrayCode => Box::new(GrayWalker::new(self).states(positions)),
+        }
+    }
+
+    /// Returns the number of unknots and the smoothing difference of the state with the given
+    /// index, using `counter` to track the regions.
+    fn state(&self, counter: &mut RegionCounter, n: u128) -> (usize, i16) {
+        let mut diff: i16 = 0;
+        let bits: BitVec<LittleEndian, _> = BitVec::from(&n.to_le_bytes()[..]);
+        counter.reset();
+        self.crossings
+            .iter()
+            .zip(bits.iter())
+            .for_each(|(crossing, bit)| {
+                diff += if bit { -1 } else { 1 };
+                let (first, second) = crossing.smoothing(bit);
+                counter.combine(first, second);
+            });
+        (counter.current_count() - 1, diff)
+    }
+
+    /// Like `resolutions`, but checks the given options between chunks of states, reporting
+    /// progress and stopping early if the computation is cancelled or runs out of time.
+    pub fn try_resolutions(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Vec<(usize, i16)>, ComputeError> {
+        let total = 2u128.pow(self.num_crossings() as u32);
+        let monitor = self.state_monitor(options, total)?;
+        let size = total.min(CHUNK_SIZE);
+        options.fold_chunks(
+            self.num_crossings(),
+            total / size,
+            Vec::new,
+            |mut states, k| {
+                monitor.check()?;
+                let mut counter = RegionCounter::new(self.num_regions());
+                states.extend((k * size..(k + 1) * size).map(|n| self.state(&mut counter, n)));
+                monitor.advance(size as u64);
+                Ok(states)
+            },
+            |mut a, mut b| {
+                a.append(&mut b);
+                a
+            },
+        )
+    }
+
+    /// Folds the states of the knot into a single value without storing them, in the manner of
+    /// `ParallelIterator::fold` followed by `ParallelIterator::reduce`. Each thread folds its
+    /// share of the states into a value starting from `identity()`, and the per-thread values
+    /// are then combined with `reduce`.
+    pub fn fold_states<T, ID, F, R>(
+        &self,
+        enumeration: Enumeration,
+        identity: ID,
+        fold: F,
+        reduce: R,
+    ) -> T
+    where
+        T: Send,
+        ID: Fn() -> T + Sync + Send,
+        F: Fn(T, (usize, i16)) -> T + Sync + Send,
+        R: Fn(T, T) -> T + Sync + Send,
+    {
+        self.try_fold_states(enumeration, &ComputeOptions::new(), identity, fold, reduce)
+            .expect("no limits were set")
+    }
+
+    /// Like `fold_states`, but runs on the threads the options give and checks them between
+    /// chunks of states, reporting progress and stopping early if the computation is cancelled
+    /// or runs out of time.
+    pub fn try_fold_states<T, ID, F, R>(
+        &self,
+        enumeration: Enumeration,
+        options: &ComputeOptions,
+        identity: ID,
+        fold: F,
+        reduce: R,
+    ) -> Result<T, ComputeError>
+    where
+        T: Send,
+        ID: Fn() -> T + Sync + Send,
+        F: Fn(T, (usize, i16)) -> T + Sync + Send,
+        R: Fn(T, T) -> T + Sync + Send,
+    {
+        let total = 2u128.pow(self.num_crossings() as u32);
+        let monitor = self.state_monitor(options, total)?;
+        let size = total.min(CHUNK_SIZE);
+        options.fold_chunks(
+            self.num_crossings(),
+            total / size,
+            identity,
+            |folded, k| {
+                monitor.check()?;
+                let folded = self
+                    .range_states(enumeration, k * size..(k + 1) * size)
+                    .fold(folded, &fold);
+                monitor.advance(size as u64);
+                Ok(folded)
+            },
+            reduce,
+        )
+    }
+
+    /// Counts the states of the knot by their number of unknots and smoothing difference.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let knot = Knot::from_str("aaa").unwrap();
+    /// let histogram = knot.state_histogram();
+    /// assert_eq!(histogram.total(), 8);
+    /// assert_eq!(histogram.get(1, 1), 3);
+    /// ```
+    pub fn state_histogram(&self) -> StateHistogram {
+        self.try_state_histogram(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Like `state_histogram`, but checks the given options between chunks of states, reporting
+    /// progress and stopping early if the computation is cancelled or runs out of time.
+    pub fn try_state_histogram(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<StateHistogram, ComputeError> {
+        self.range_histogram(0..2u128.pow(self.num_crossings() as u32), options)
+    }
+
+    /// Counts the states at the given positions of the Gray-code order, in chunks that are
+    /// checked against the options.
+    fn range_histogram(
+        &self,
+        range: Range<u128>,
+        options: &ComputeOptions,
+    ) -> Result<StateHistogram, ComputeError> {
+        let crossings = self.num_crossings();
+        let total = range.end.saturating_sub(range.start);
+        let monitor = self.state_monitor(options, total)?;
+        let chunks = total.div_ceil(CHUNK_SIZE);
+        options.fold_chunks(
+            crossings,
+            chunks,
+            || StateHistogram::new(crossings),
+            |mut histogram, k| {
+                monitor.check()?;
+                let start = range.start + k * CHUNK_SIZE;
+                let end = range.end.min(start + CHUNK_SIZE);
+                for (circles, diff) in GrayWalker::new(self).states(start..end) {
+                    histogram.add(circles, diff);
+                }
+                monitor.advance((end - start) as u64);
+                Ok(histogram)
+            },
+            StateHistogram::merge,
+        )
+    }
+
+    /// Checks the state limit and starts tracking a state sum over `total` states.
+    fn state_monitor<'a>(
+        &self,
+        options: &'a ComputeOptions,
+        total: u128,
+    ) -> Result<Monitor<'a>, ComputeError> {
+        let states = u64::try_from(total).unwrap_or(u64::MAX);
+        options::check_states(options, states)?;
+        Ok(Monitor::new(options, Some(states)))
     }
 
     /// Returns the writhe of the knot, which is the number of positive crossings minus the number
     /// of negative crossings.
     pub fn writhe(&self) -> isize {
         self.crossings
-            .par_iter()
+            .iter()
             .map(|c| match c.orientation {
                 Orientation::Positive => 1,
                 Orientation::Negative => -1,
@@ -67,34 +302,75 @@ impl Knot {
 
     /// Returns the bracket polynomial of the knot.
     pub fn bracket_polynomial(&self) -> Polynomial {
-        self.resolutions()
-            .par_iter()
-            .map(|(c, d)| {
-                Binomial(Term::new(-1, 2), Term::new(-1, -2)).expand(*c as isize - 1)
-                    * Term::new(1, *d as isize)
-            })
-            .sum()
+        self.bracket_polynomial_with(BracketMethod::StateSum)
+    }
+
+    /// Returns the bracket polynomial of the knot, computed with the given method.
+    pub fn bracket_polynomial_with(&self, method: BracketMethod) -> Polynomial {
+        self.try_bracket_polynomial(&ComputeOptions::new().method(method))
+            .expect("no limits were set")
+    }
+
+    /// Returns the bracket polynomial of the knot, computed with the given options, or an error
+    /// if the computation was stopped early.
+    pub fn try_bracket_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        match options.bracket_method() {
+            BracketMethod::StateSum => Ok(self.try_state_histogram(options)?.bracket_polynomial()),
+            BracketMethod::Tait => self
+                .tait_graph()
+                .monitored_bracket(&Monitor::new(options, None)),
+            BracketMethod::Decomposition => self
+                .decomposition_bracket(&Monitor::new(options, Some(self.num_crossings() as u64))),
+        }
     }
 
     /// Returns the beta polynomial of the knot, obtained by multiplying the bracket polynomial by
     /// `(-A)^(-3w)`, where w is the writhe of the knot.
     pub fn beta_polynomial(&self) -> Polynomial {
-        let w = self.writhe();
-        self.bracket_polynomial()
-            * Term::new(
-                if w % 2 == 0 { 1 } else { -1 }, /*(-1f64).pow(w as i32).signum() as isize*/
-                -3 * self.writhe(),
-            )
+        self.beta_polynomial_with(BracketMethod::StateSum)
     }
 
-    /// Returns the jones polynomial of the knot, obtained by plugging in `A^(-1/4)` for every `A`
-    /// in the beta polynomial.
+    /// Returns the beta polynomial of the knot, with the bracket computed using the given method.
+    pub fn beta_polynomial_with(&self, method: BracketMethod) -> Polynomial {
+        self.try_beta_polynomial(&ComputeOptions::new().method(method))
+            .expect("no limits were set")
+    }
+
+    /// Returns the beta polynomial of the knot, computed with the given options, or an error if
+    /// the computation was stopped early.
+    pub fn try_beta_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        Ok(beta_from_bracket(
+            self.try_bracket_polynomial(options)?,
+            self.writhe(),
+        ))
+    }
+
+    /// Returns the jones polynomial of the knot in `t`, obtained by plugging in `t^(-1/4)` for
+    /// every `A` in the beta polynomial.
     pub fn jones_polynomial(&self) -> Polynomial {
-        self.beta_polynomial()
-            .par_iter()
-            .map(|t| Term::new(t.coefficient(), t.exponent() / -4))
-            .collect::<Vec<_>>()
-            .into()
+        self.jones_polynomial_with(BracketMethod::StateSum)
+    }
+
+    /// Returns the jones polynomial of the knot, with the bracket computed using the given
+    /// method.
+    pub fn jones_polynomial_with(&self, method: BracketMethod) -> Polynomial {
+        self.try_jones_polynomial(&ComputeOptions::new().method(method))
+            .expect("no limits were set")
+    }
+
+    /// Returns the jones polynomial of the knot, computed with the given options, or an error if
+    /// the computation was stopped early.
+    pub fn try_jones_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        Ok(jones_from_beta(self.try_beta_polynomial(options)?))
     }
 
     /// Creates a knot from a provided list of `CrossingBuilder`s.
@@ -195,24 +471,100 @@ impl FromStr for Knot {
     }
 }
 
+/// Multiplies a bracket polynomial by `(-A)^(-3w)` for a diagram with writhe `w`.
+fn beta_from_bracket(bracket: Polynomial, writhe: isize) -> Polynomial {
+    bracket
+        * Term::new(
+            if writhe % 2 == 0 { 1 } else { -1 }, /*(-1f64).pow(w as i32).signum() as isize*/
+            -3 * writhe,
+        )
+}
+
+/// Plugs in `t^(-1/4)` for every `A` in a beta polynomial.
+fn jones_from_beta(beta: Polynomial) -> Polynomial {
+    let t = Polynomial::from(Term::new(Rational::from_integer(1), Rational::new(-1, 4)));
+    beta.substitute(&t.with_variable('t'))
+        .expect("substituting a monic monomial always succeeds")
+}
+
+/// The ways the bracket polynomial of a knot can be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketMethod {
+    /// Sums over all `2^n` states of the diagram.
+    StateSum,
+    /// Uses deletion and contraction on the Tait graph of the diagram, which is usually much
+    /// faster for larger diagrams.
+    Tait,
+    /// Adds the crossings one at a time, keeping track of how the regions on the boundary of
+    /// the processed part are connected. This takes time exponential only in
+    /// `Knot::decomposition_width`, so it can handle diagrams with hundreds of crossings as long
+    /// as they are narrow.
+    Decomposition,
+}
+
+impl BracketMethod {
+    /// The names accepted by `BracketMethod::from_str`.
+    pub const NAMES: &'static [&'static str] = &["states", "tait", "decomposition"];
+}
+
+impl FromStr for BracketMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "states" | "state-sum" => Ok(BracketMethod::StateSum),
+            "tait" => Ok(BracketMethod::Tait),
+            "decomposition" | "path" => Ok(BracketMethod::Decomposition),
+            _ => Err(format!(
+                "unknown method '{}', expected one of: {}",
+                s,
+                BracketMethod::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum KnotParseError {
     InvalidCharacter(Vec<char>),
+    /// A planar diagram code that does not describe a diagram, with the reason.
+    InvalidPlanarDiagram(String),
 }
 
 /// Represents one crossing of two strands in a knot.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Crossing {
     top: usize,
     bottom: usize,
     left: usize,
     right: usize,
-    column: u8,
     orientation: Orientation,
 }
 
+impl Crossing {
+    /// Returns the two regions joined by smoothing the crossing. A `false` bit takes the 0
+    /// resolution and a `true` bit the infinity resolution.
+    fn smoothing(&self, bit: bool) -> (usize, usize) {
+        // TODO: might need to switch
+        match (self.orientation, bit) {
+            (Orientation::Positive, false) | (Orientation::Negative, true) => {
+                (self.top, self.bottom)
+            }
+            (Orientation::Positive, true) | (Orientation::Negative, false) => {
+                (self.left, self.right)
+            }
+        }
+    }
+}
+
 /// The two possible orientations for a `Crossing`.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 enum Orientation {
     Positive,
     Negative,
@@ -237,7 +589,6 @@ impl CrossingBuilder {
             bottom: self.bottom.ok_or(())?,
             left: self.left.ok_or(())?,
             right: self.right.ok_or(())?,
-            column: self.column,
             orientation: self.orientation,
         })
     }
@@ -256,58 +607,74 @@ impl CrossingBuilder {
 }
 
 /// A utility for combining regions in an efficient manner.
+///
+/// Regions are tracked in a flat union–find structure with path compression and union by rank,
+/// so `combine` runs in near-constant time and `reset` reuses the same allocation.
 pub struct RegionCounter {
     count: usize,
-    regions: Vec<HashSet<usize>>,
+    start: usize,
+    parent: Vec<usize>,
+    rank: Vec<u8>,
 }
 
 impl RegionCounter {
-    /// Creates a new `RegionCounter` with `start` as the initial number of regions.
+    /// Creates a new `RegionCounter` with `start` as the initial number of regions, numbered
+    /// from `0` to `start - 1`.
     pub fn new(start: usize) -> Self {
         Self {
             count: start,
-            regions: Vec::new(),
+            start,
+            parent: (0..start).collect(),
+            rank: vec![0; start],
+        }
+    }
+
+    /// Separates every region again, as if the counter had just been created.
+    pub fn reset(&mut self) {
+        self.count = self.start;
+        for (i, p) in self.parent.iter_mut().enumerate() {
+            *p = i;
+        }
+        for r in self.rank.iter_mut() {
+            *r = 0;
         }
     }
 
     /// Combines the two regions passed in, reducing the total count if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either region is not less than the initial number of regions.
     pub fn combine(&mut self, first: usize, second: usize) {
-        if first == second {
+        let (a, b) = (self.find(first), self.find(second));
+        if a == b {
             return;
         }
-        let mut temp: Vec<&mut HashSet<usize>> = self
-            .regions
-            .iter_mut()
-            .filter(|r| r.contains(&first) || r.contains(&second))
-            .collect();
-        if temp.len() >= 2 {
-            self.count -= 1;
-
-            let new_set = temp
-                .iter()
-                .fold(HashSet::new(), |acc, e| acc.union(e).cloned().collect());
-            self.regions
-                .retain(|r| !(r.contains(&first) || r.contains(&second)));
-            self.regions.push(new_set);
-        } else if temp.is_empty() {
-            self.count -= 1;
-
-            let mut set = HashSet::new();
-            set.insert(first);
-            set.insert(second);
-            self.regions.push(set);
-        } else {
-            let set = temp.pop().unwrap();
-            if set.insert(first) || set.insert(second) {
-                self.count -= 1;
+
+        match self.rank[a].cmp(&self.rank[b]) {
+            Ordering::Less => self.parent[a] = b,
+            Ordering::Greater => self.parent[b] = a,
+            Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
             }
         }
+        self.count -= 1;
     }
 
     /// Returns the current number of regions counted.
-    fn current_count(&self) -> usize {
+    pub fn current_count(&self) -> usize {
         self.count
     }
+
+    /// Returns the representative of the region's set, halving the path to it on the way.
+    fn find(&mut self, mut region: usize) -> usize {
+        while self.parent[region] != region {
+            self.parent[region] = self.parent[self.parent[region]];
+            region = self.parent[region];
+        }
+        region
+    }
 }
 
 #[cfg(test)]
@@ -362,6 +729,26 @@ mod tests {
             counter.combine(5, 2);
             assert_eq!(3, counter.current_count());
         }
+
+        #[test]
+        fn reset() {
+            let mut counter = RegionCounter::new(5);
+            counter.combine(0, 1);
+            counter.combine(2, 3);
+            counter.combine(1, 3);
+            assert_eq!(2, counter.current_count());
+
+            counter.reset();
+            assert_eq!(5, counter.current_count());
+
+            // Regions combined before the reset are separate again.
+            counter.combine(0, 3);
+            assert_eq!(4, counter.current_count());
+            counter.combine(1, 2);
+            assert_eq!(3, counter.current_count());
+            counter.combine(4, 4);
+            assert_eq!(3, counter.current_count());
+        }
     }
 
     mod knot_parsing {
@@ -408,8 +795,12 @@ mod tests {
 
     mod resolving {
         use super::unknots;
-        use crate::Knot;
+        use crate::{ComputeOptions, Enumeration, Knot};
+        #[cfg(feature = "rayon")]
+        use rayon::prelude::*;
         use std::str::FromStr;
+        #[cfg(feature = "rayon")]
+        use std::sync::Arc;
 
         #[test]
         fn basics() {
@@ -444,10 +835,83 @@ mod tests {
             resolutions.sort();
             assert_eq!(resolutions, vec![3, 4, 4, 5]);
         }
+
+        #[test]
+        fn gray_code() {
+            for braid in &["abc", "acb", "bd", "aBcAbCa", "abbaABBAc"] {
+                let knot = Knot::from_str(braid).unwrap();
+                let mut direct = knot.resolutions();
+                let mut gray = knot.resolutions_with(Enumeration::GrayCode);
+                direct.sort();
+                gray.sort();
+                assert_eq!(direct, gray);
+            }
+        }
+
+        #[test]
+        fn streaming() {
+            for braid in &["abc", "aBcAbCa", "abbaABBAc"] {
+                let knot = Knot::from_str(braid).unwrap();
+                let resolutions = knot.resolutions();
+
+                let histogram = knot.state_histogram();
+                assert_eq!(histogram.total(), resolutions.len() as u64);
+                for &(c, d) in &resolutions {
+                    let count = resolutions.iter().filter(|&&s| s == (c, d)).count();
+                    assert_eq!(histogram.get(c, d), count as u64);
+                }
+
+                let circles = knot.fold_states(
+                    Enumeration::Direct,
+                    || 0,
+                    |sum, (c, _)| sum + c,
+                    |a, b| a + b,
+                );
+                assert_eq!(circles, resolutions.iter().map(|&(c, _)| c).sum::<usize>());
+
+                for options in &[ComputeOptions::new(), ComputeOptions::new().sequential()] {
+                    let gray = knot.try_fold_states(
+                        Enumeration::GrayCode,
+                        options,
+                        || 0,
+                        |sum, (c, _)| sum + c,
+                        |a, b| a + b,
+                    );
+                    assert_eq!(gray.unwrap(), circles);
+                }
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn parallel_states() {
+            let knot = Knot::from_str("aBcAbCa").unwrap();
+            for &enumeration in &[Enumeration::Direct, Enumeration::GrayCode] {
+                let mut states: Vec<_> = knot.states(enumeration).collect();
+                let mut parallel: Vec<_> = knot.par_states(enumeration).collect();
+                states.sort();
+                parallel.sort();
+                assert_eq!(states, parallel);
+            }
+
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap();
+            let options = ComputeOptions::new().thread_pool(Arc::new(pool));
+            let count = knot.try_fold_states(
+                Enumeration::Direct,
+                &options,
+                || 0,
+                |count, _| count + 1,
+                |a, b| a + b,
+            );
+            assert_eq!(count.unwrap(), 128);
+        }
     }
 
     mod polynomial_generation {
-        use crate::{polynomial::*, Knot};
+        use crate::{polynomial::*, BracketMethod, Knot};
         use std::str::FromStr;
 
         #[test]
@@ -472,5 +936,24 @@ mod tests {
                 Polynomial::from_vec(vec![Term::new(1, -7), Term::new(-1, -3), Term::new(-1, 5)])
             );
         }
+
+        #[test]
+        fn jones() {
+            let jones = Knot::from_str("aaa").unwrap().jones_polynomial();
+            assert_eq!(jones, crate::polynomial!("t + t^3 - t^4"));
+            assert_eq!(jones.variable(), 't');
+            assert_eq!(jones.to_string(), "-1t^4 + 1t^3 + 1t^1");
+        }
+
+        #[test]
+        fn methods() {
+            let knot = Knot::from_str("aBaBaB").unwrap();
+            assert_eq!(
+                knot.jones_polynomial_with(BracketMethod::Tait),
+                knot.jones_polynomial()
+            );
+            assert_eq!("tait".parse(), Ok(BracketMethod::Tait));
+            assert!("magic".parse::<BracketMethod>().is_err());
+        }
     }
 }
@@ -0,0 +1,478 @@
+//! Markov moves, which change a braid without changing its closure, and a search that uses them
+//! to make braids smaller.
+//!
+//! By Markov's theorem, two braids have the same closure exactly when one can be turned into the
+//! other by conjugation, by the relations of the braid group, and by stabilisation, which adds a
+//! strand that is crossed once at the end of the word. Destabilisation undoes a stabilisation.
+//! A closure with fewer crossings has fewer states, so simplifying a braid first makes every
+//! invariant of its closure cheaper to compute.
+
+use crate::{
+    beta_from_bracket, braid::Braid, polynomial::Polynomial, ComputeError, ComputeOptions, Knot,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
+
+/// The most words that `Braid::simplify` looks through when it cannot make a word shorter
+/// directly.
+const SLIDE_LIMIT: usize = 1000;
+
+/// A change to a braid that does not change its closure.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkovMove {
+    /// Replaces the word `w` by `c^-1 w c` for the given braid `c`, then cancels the letters
+    /// that meet their inverses.
+    Conjugate(Braid),
+    /// Replaces the word by the equivalent one given by `Braid::reduce`.
+    Reduce,
+    /// Replaces the word by the given one, which is the same braid written differently.
+    Rewrite(Braid),
+    /// Adds a strand on the right, crossed by the given generator at the end of the word.
+    Stabilise(isize),
+    /// Removes the given generator from the end of the word, where it is the only use of `σ1`
+    /// or of the last generator, along with the strand at that side.
+    Destabilise(isize),
+}
+
+impl MarkovMove {
+    /// Applies the move to a braid, or returns `None` if it does not apply.
+    ///
+    /// ```
+    /// use knot_solver::braid::{Braid, MarkovMove};
+    ///
+    /// let braid: Braid = "aaab".parse().unwrap();
+    /// let smaller = MarkovMove::Destabilise(2).apply(&braid).unwrap();
+    /// assert_eq!(smaller, "aaa".parse().unwrap());
+    /// assert_eq!(MarkovMove::Destabilise(1).apply(&braid), None);
+    /// ```
+    pub fn apply(&self, braid: &Braid) -> Option<Braid> {
+        match self {
+            MarkovMove::Conjugate(c) if c.strands() <= braid.strands() => {
+                Some(braid.conjugate(c).free_reduce())
+            }
+            MarkovMove::Conjugate(_) => None,
+            MarkovMove::Reduce => Some(braid.reduce()),
+            MarkovMove::Rewrite(b) if b.strands() == braid.strands() && b.is_equivalent(braid) => {
+                Some(b.clone())
+            }
+            MarkovMove::Rewrite(_) => None,
+            MarkovMove::Stabilise(g) if g.unsigned_abs() == braid.strands() => {
+                Some(braid.stabilise(*g > 0))
+            }
+            MarkovMove::Stabilise(_) => None,
+            MarkovMove::Destabilise(g) if braid.word().last() == Some(g) => braid.destabilise(),
+            MarkovMove::Destabilise(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for MarkovMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let letter = |g: isize| Braid::new(g.unsigned_abs() + 1, vec![g]);
+        match self {
+            MarkovMove::Conjugate(c) => write!(f, "conjugate by {}", c),
+            MarkovMove::Reduce => write!(f, "reduce"),
+            MarkovMove::Rewrite(b) => write!(f, "rewrite as {}", b),
+            MarkovMove::Stabilise(g) => write!(f, "stabilise with {}", letter(*g)),
+            MarkovMove::Destabilise(g) => write!(f, "destabilise {}", letter(*g)),
+        }
+    }
+}
+
+/// A braid with the same closure as another one, found by `Braid::simplify`, along with the
+/// moves that lead to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simplification {
+    braid: Braid,
+    moves: Vec<MarkovMove>,
+    /// The writhe of the closure of the original braid.
+    writhe: isize,
+}
+
+impl Simplification {
+    /// Returns the simplified braid.
+    pub fn braid(&self) -> &Braid {
+        &self.braid
+    }
+
+    /// Returns the moves that turn the original braid into the simplified one, in order.
+    pub fn moves(&self) -> &[MarkovMove] {
+        &self.moves
+    }
+
+    /// Returns the closure of the simplified braid.
+    pub fn closure(&self) -> Knot {
+        self.braid.closure()
+    }
+
+    /// Computes the bracket polynomial of the closure of the original braid from the simplified
+    /// one. The bracket polynomial changes with the writhe, which is corrected for.
+    pub fn try_bracket_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        let bracket = self.closure().try_bracket_polynomial(options)?;
+        Ok(beta_from_bracket(
+            bracket,
+            self.braid.exponent_sum() - self.writhe,
+        ))
+    }
+
+    /// Computes the beta polynomial of the closure, which is the same for both braids.
+    pub fn try_beta_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        self.closure().try_beta_polynomial(options)
+    }
+
+    /// Computes the jones polynomial of the closure, which is the same for both braids.
+    pub fn try_jones_polynomial(
+        &self,
+        options: &ComputeOptions,
+    ) -> Result<Polynomial, ComputeError> {
+        self.closure().try_jones_polynomial(options)
+    }
+
+    /// Computes the bracket polynomial of the closure of the original braid.
+    pub fn bracket_polynomial(&self) -> Polynomial {
+        self.try_bracket_polynomial(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Computes the beta polynomial of the closure.
+    pub fn beta_polynomial(&self) -> Polynomial {
+        self.try_beta_polynomial(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Computes the jones polynomial of the closure.
+    pub fn jones_polynomial(&self) -> Polynomial {
+        self.try_jones_polynomial(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+}
+
+impl Braid {
+    /// Returns the braid `c^-1 b c`, which has the same closure as `b`. The result has as many
+    /// strands as the larger of the two.
+    pub fn conjugate(&self, by: &Braid) -> Self {
+        &(&by.inverse() * self) * by
+    }
+
+    /// Adds a strand on the right, crossed once by the last strand at the end of the word. The
+    /// closure is the same, with one more crossing.
+    pub fn stabilise(&self, positive: bool) -> Self {
+        let g = self.strands() as isize;
+        let mut word = self.word().to_vec();
+        word.push(if positive { g } else { -g });
+        Braid::new(self.strands() + 1, word)
+    }
+
+    /// Undoes a stabilisation, if the word ends with its only use of the last generator, or of
+    /// `σ1`. That letter is removed along with the strand at that side, which nothing else
+    /// crosses, and the closure stays the same.
+    pub fn destabilise(&self) -> Option<Self> {
+        let (&last, rest) = self.word().split_last()?;
+        let used_once = |g: usize| rest.iter().all(|h| h.unsigned_abs() != g);
+        let strands = self.strands() - 1;
+        if last.unsigned_abs() == strands && used_once(strands) {
+            Some(Braid::new(strands, rest.to_vec()))
+        } else if last.abs() == 1 && used_once(1) {
+            let shifted = rest.iter().map(|&g| g - g.signum()).collect();
+            Some(Braid::new(strands, shifted))
+        } else {
+            None
+        }
+    }
+
+    /// Looks for a braid with the same closure and as few letters as possible, using
+    /// reduction, conjugation and destabilisation.
+    ///
+    /// Moves that make the word shorter are applied while there are any: reducing the word,
+    /// conjugating away a first letter that is the inverse of the last one, and destabilising
+    /// `σ1` or the last generator when it is used once, after conjugating the word so that it
+    /// ends with that letter. When none of these apply, the words of the same length that come
+    /// from rotating the word and from the relations `σi σj = σj σi` for `|i - j| > 1`,
+    /// `σi σj σi = σj σi σj` and `σi^e σj^f σi^-e = σj^-e σi^f σj^e` for `|i - j| = 1` are
+    /// searched for one that can be made shorter. If there is none, each conjugation by a single
+    /// generator is tried, and the one that leads to the smallest braid is kept if it is smaller
+    /// than the current one. The result is not always the smallest braid with the same closure.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// // A trefoil with an extra strand, conjugated by `σ2`.
+    /// let braid: Braid = "BaaabB".parse().unwrap();
+    /// let simplified = braid.simplify();
+    /// assert_eq!(simplified.braid(), &"aaa".parse().unwrap());
+    /// assert_eq!(
+    ///     simplified.jones_polynomial(),
+    ///     braid.closure().jones_polynomial()
+    /// );
+    /// ```
+    pub fn simplify(&self) -> Simplification {
+        let mut search = Search {
+            braid: self.clone(),
+            moves: Vec::new(),
+        };
+        search.descend();
+        loop {
+            if search.slide() {
+                search.descend();
+                continue;
+            }
+            let n = search.braid.strands() as isize;
+            let best = (1..n)
+                .flat_map(|g| vec![g, -g])
+                .map(|g| {
+                    let mut trial = Search {
+                        braid: search.braid.clone(),
+                        moves: Vec::new(),
+                    };
+                    trial.apply(MarkovMove::Conjugate(Braid::new(n as usize, vec![g])));
+                    trial.descend();
+                    trial
+                })
+                .min_by_key(Search::size);
+            match best {
+                Some(trial) if trial.size() < search.size() => {
+                    search.braid = trial.braid;
+                    search.moves.extend(trial.moves);
+                }
+                _ => break,
+            }
+        }
+        Simplification {
+            braid: search.braid,
+            moves: search.moves,
+            writhe: self.exponent_sum(),
+        }
+    }
+}
+
+/// A braid and the moves that have been applied to get it.
+struct Search {
+    braid: Braid,
+    moves: Vec<MarkovMove>,
+}
+
+impl Search {
+    /// The number of letters and then the number of strands, which every move applied by
+    /// `descend` makes smaller.
+    fn size(&self) -> (usize, usize) {
+        (self.braid.len(), self.braid.strands())
+    }
+
+    fn apply(&mut self, m: MarkovMove) {
+        self.braid = m.apply(&self.braid).expect("the move applies");
+        self.moves.push(m);
+    }
+
+    /// Applies moves that make the braid smaller until there are none left.
+    fn descend(&mut self) {
+        loop {
+            let strands = self.braid.strands();
+            let word = self.braid.word().to_vec();
+            if self.braid.reduce().len() < word.len() {
+                self.apply(MarkovMove::Reduce);
+            } else if word.len() > 1 && word[0] == -word[word.len() - 1] {
+                self.apply(MarkovMove::Conjugate(Braid::new(strands, vec![word[0]])));
+            } else if let Some(end) = (0..word.len()).find(|&i| {
+                let g = word[i].unsigned_abs();
+                (g == 1 || g == strands - 1)
+                    && word.iter().filter(|h| h.unsigned_abs() == g).count() == 1
+            }) {
+                if end + 1 < word.len() {
+                    let prefix = Braid::new(strands, word[..=end].to_vec());
+                    self.apply(MarkovMove::Conjugate(prefix));
+                }
+                self.apply(MarkovMove::Destabilise(word[end]));
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Looks through the words of the same length that can be reached by rotating the word and
+    /// rewriting it with the braid relations, nearest first, for one that `descend` can make
+    /// smaller. Returns whether one was found and moved to.
+    fn slide(&mut self) -> bool {
+        let strands = self.braid.strands();
+        let start = self.braid.word().to_vec();
+        let mut seen = HashSet::new();
+        seen.insert(start.clone());
+        // Each word is kept with the braid that conjugates the starting word to it.
+        let mut queue = VecDeque::new();
+        queue.push_back((start, Vec::new()));
+        while let Some((word, conjugator)) = queue.pop_front() {
+            if shrinks(&word, strands) {
+                let conjugator = Braid::new(strands, conjugator).free_reduce();
+                if !conjugator.is_empty() {
+                    self.apply(MarkovMove::Conjugate(conjugator));
+                }
+                let word = Braid::new(strands, word);
+                if self.braid != word {
+                    self.apply(MarkovMove::Rewrite(word));
+                }
+                return true;
+            }
+            for (next, letter) in neighbours(&word) {
+                if seen.len() < SLIDE_LIMIT && seen.insert(next.clone()) {
+                    let mut conjugator = conjugator.clone();
+                    conjugator.extend(letter);
+                    queue.push_back((next, conjugator));
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Whether `Search::descend` can make a word shorter without handle reduction: some letter
+/// meets its inverse, perhaps around the ends of the word, or `σ1` or the last generator is used
+/// once.
+fn shrinks(word: &[isize], strands: usize) -> bool {
+    let n = word.len();
+    (0..n).any(|i| n > 1 && word[i] == -word[(i + 1) % n])
+        || [1, strands.saturating_sub(1)]
+            .iter()
+            .any(|&g| word.iter().filter(|h| h.unsigned_abs() == g).count() == 1)
+}
+
+/// The words that come from rotating a word by one letter, along with the letter that
+/// conjugates the word to the rotated one, and from rewriting two or three neighbouring
+/// letters with a braid relation, which needs no conjugation.
+fn neighbours(word: &[isize]) -> Vec<(Vec<isize>, Option<isize>)> {
+    let mut neighbours = Vec::new();
+    if let (Some(&first), Some(&last)) = (word.first(), word.last()) {
+        let mut left = word[1..].to_vec();
+        left.push(first);
+        neighbours.push((left, Some(first)));
+        let mut right = vec![last];
+        right.extend_from_slice(&word[..word.len() - 1]);
+        neighbours.push((right, Some(-last)));
+    }
+    for i in 0..word.len() {
+        let mut rewrite = |at: usize, letters: &[isize]| {
+            let mut next = word.to_vec();
+            next[at..at + letters.len()].copy_from_slice(letters);
+            neighbours.push((next, None));
+        };
+        if let [x, y, ..] = word[i..] {
+            if (x.abs() - y.abs()).abs() > 1 {
+                rewrite(i, &[y, x]);
+            }
+        }
+        if let [x, y, z, ..] = word[i..] {
+            let (e, f) = (x.signum(), y.signum());
+            if (x.abs() - y.abs()).abs() == 1 {
+                if z == x && e == f {
+                    rewrite(i, &[y, x, y]);
+                } else if z == -x {
+                    rewrite(i, &[-e * y.abs(), f * x.abs(), e * y.abs()]);
+                }
+            }
+        }
+    }
+    neighbours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MarkovMove;
+    use crate::braid::Braid;
+    use std::str::FromStr;
+
+    fn braid(s: &str) -> Braid {
+        Braid::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn moves() {
+        let trefoil = braid("aaa");
+        let stabilised = trefoil.stabilise(false);
+        assert_eq!(stabilised.to_string(), "aaaB");
+        assert_eq!(stabilised.destabilise(), Some(trefoil.clone()));
+        assert_eq!(
+            MarkovMove::Stabilise(-2).apply(&trefoil),
+            Some(stabilised.clone())
+        );
+        assert_eq!(MarkovMove::Stabilise(3).apply(&trefoil), None);
+        // `σ1` can be removed from the left, moving the other strands over.
+        assert_eq!(braid("bbba").destabilise(), Some(trefoil.clone()));
+        assert_eq!(braid("aaba").destabilise(), None);
+        assert_eq!(braid("aba").destabilise(), None);
+        assert_eq!(Braid::identity(3).destabilise(), None);
+
+        let c = braid("ab");
+        assert_eq!(trefoil.conjugate(&c).to_string(), "BAaaaab");
+        assert_eq!(
+            MarkovMove::Conjugate(c.clone()).apply(&stabilised),
+            Some(braid("BaaBab"))
+        );
+        assert_eq!(
+            MarkovMove::Conjugate(braid("a")).apply(&trefoil),
+            Some(trefoil.clone())
+        );
+        // Conjugating by a braid on more strands would add a component to the closure.
+        assert_eq!(MarkovMove::Conjugate(c).apply(&trefoil), None);
+
+        assert_eq!(MarkovMove::Reduce.to_string(), "reduce");
+        assert_eq!(MarkovMove::Destabilise(-2).to_string(), "destabilise B");
+        assert_eq!(
+            MarkovMove::Conjugate(braid("aB")).to_string(),
+            "conjugate by aB"
+        );
+    }
+
+    #[test]
+    fn simplify() {
+        let words = [
+            "abc",
+            "aaab",
+            "aBcbAC",
+            "BaaabB",
+            "cbaaabcBCd",
+            "abAB",
+            "aBaBcbC",
+            "bbaaBBAA",
+        ];
+        for word in &words {
+            let original = braid(word);
+            let simplified = original.simplify();
+            assert!(simplified.braid().len() <= original.len(), "{}", word);
+            let replayed = simplified
+                .moves()
+                .iter()
+                .try_fold(original.clone(), |b, m| m.apply(&b));
+            assert_eq!(replayed.as_ref(), Some(simplified.braid()), "{}", word);
+            assert_eq!(
+                simplified.bracket_polynomial(),
+                original.closure().bracket_polynomial(),
+                "{}",
+                word
+            );
+            assert_eq!(
+                simplified.jones_polynomial(),
+                original.closure().jones_polynomial(),
+                "{}",
+                word
+            );
+        }
+
+        // Stabilised unknots destabilise down to a single strand, and unlinks to one strand for
+        // each component.
+        assert_eq!(braid("abc").simplify().braid(), &Braid::identity(1));
+        assert_eq!(braid("aBcbAC").simplify().braid(), &Braid::identity(2));
+        // Conjugating a stabilised trefoil hides the stabilisation until it is conjugated back.
+        let hidden = braid("aaab").conjugate(&braid("bab"));
+        assert_eq!(hidden.simplify().braid(), &braid("aaa"));
+        // Reaching the stabilisation needs `σ3^-1 σ2 σ3 = σ2 σ3 σ2^-1`.
+        assert_eq!(braid("cbaaabcBCd").simplify().braid(), &braid("aaa"));
+        // Strands that are not crossed are separate components, so they stay.
+        assert_eq!(Braid::new(3, vec![1, 1]).simplify().braid().strands(), 3);
+    }
+}
@@ -0,0 +1,429 @@
+use crate::BracketMethod;
+#[cfg(feature = "rayon")]
+use rayon::{prelude::*, ThreadPool};
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Settings for a long computation, such as `Knot::try_bracket_polynomial`.
+///
+/// ```
+/// use knot_solver::{ComputeOptions, Knot};
+/// use std::{str::FromStr, time::Duration};
+///
+/// let options = ComputeOptions::new()
+///     .time_limit(Duration::from_secs(10))
+///     .on_progress(|p| eprintln!("{} of {:?}", p.done, p.total));
+/// let bracket = Knot::from_str("aBaB").unwrap().try_bracket_polynomial(&options);
+/// assert!(bracket.is_ok());
+/// ```
+pub struct ComputeOptions {
+    method: BracketMethod,
+    progress: Option<Box<dyn Fn(Progress) + Send + Sync>>,
+    cancellation: Option<CancellationToken>,
+    time_limit: Option<Duration>,
+    state_limit: Option<u64>,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<Arc<ThreadPool>>,
+    sequential_below: usize,
+}
+
+/// The number of crossings below which computations run on the calling thread by default.
+const SEQUENTIAL_BELOW: usize = 16;
+
+impl ComputeOptions {
+    /// Creates options that sum over states, with no limits and no progress reports. Diagrams
+    /// with fewer than 16 crossings are computed on the calling thread, and larger ones on the
+    /// global rayon pool.
+    pub fn new() -> Self {
+        ComputeOptions {
+            method: BracketMethod::StateSum,
+            progress: None,
+            cancellation: None,
+            time_limit: None,
+            state_limit: None,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+            sequential_below: SEQUENTIAL_BELOW,
+        }
+    }
+
+    /// Sets the method used to compute the bracket polynomial.
+    pub fn method(mut self, method: BracketMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets a function to call as the computation progresses. It may be called from several
+    /// threads at once.
+    pub fn on_progress<F: Fn(Progress) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.progress = Some(Box::new(f));
+        self
+    }
+
+    /// Stops the computation with `ComputeError::Cancelled` once the token is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Stops the computation with `ComputeError::TimedOut` once it has run for this long.
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Refuses to sum over more than this many states, failing straight away with
+    /// `ComputeError::TooManyStates` instead. This only applies to `BracketMethod::StateSum`.
+    pub fn state_limit(mut self, limit: u64) -> Self {
+        self.state_limit = Some(limit);
+        self
+    }
+
+    /// Runs parallel computations on the given pool instead of the global one.
+    #[cfg(feature = "rayon")]
+    pub fn thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
+    /// Computes diagrams with fewer than this many crossings on the calling thread, where
+    /// splitting the work up would cost more than it saves.
+    pub fn sequential_below(mut self, crossings: usize) -> Self {
+        self.sequential_below = crossings;
+        self
+    }
+
+    /// Computes everything on the calling thread, so that the work is done, and progress is
+    /// reported, in the same order every time.
+    pub fn sequential(self) -> Self {
+        self.sequential_below(usize::MAX)
+    }
+
+    /// Returns the method used to compute the bracket polynomial.
+    pub fn bracket_method(&self) -> BracketMethod {
+        self.method
+    }
+
+    /// Folds the chunks `0..chunks` of a computation on a diagram with the given number of
+    /// crossings, in parallel unless the options say otherwise. Chunks are combined in order.
+    pub(crate) fn fold_chunks<T, ID, F, R>(
+        &self,
+        crossings: usize,
+        chunks: u128,
+        identity: ID,
+        fold: F,
+        reduce: R,
+    ) -> Result<T, ComputeError>
+    where
+        T: Send,
+        ID: Fn() -> T + Sync + Send,
+        F: Fn(T, u128) -> Result<T, ComputeError> + Sync + Send,
+        R: Fn(T, T) -> T + Sync + Send,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            if crossings >= self.sequential_below {
+                let parallel = || {
+                    (0..chunks)
+                        .into_par_iter()
+                        .try_fold(&identity, &fold)
+                        .try_reduce(&identity, |a, b| Ok(reduce(a, b)))
+                };
+                return match &self.thread_pool {
+                    Some(pool) => pool.install(parallel),
+                    None => parallel(),
+                };
+            }
+        }
+        #[cfg(not(feature = "rayon"))]
+        let _ = (crossings, &reduce);
+        (0..chunks).try_fold(identity(), fold)
+    }
+}
+
+impl Default for ComputeOptions {
+    fn default() -> Self {
+        ComputeOptions::new()
+    }
+}
+
+impl fmt::Debug for ComputeOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("ComputeOptions")
+            .field("method", &self.method)
+            .field("progress", &self.progress.is_some())
+            .field("cancellation", &self.cancellation)
+            .field("time_limit", &self.time_limit)
+            .field("state_limit", &self.state_limit)
+            .field("sequential_below", &self.sequential_below)
+            .finish()
+    }
+}
+
+/// A handle that cancels the computations it is passed to. Clones share the same state, so one
+/// can be kept to cancel a computation running on another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancels every computation using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a computation has got.
+///
+/// The units depend on the method: states for `BracketMethod::StateSum`, crossings for
+/// `BracketMethod::Decomposition` and subgraphs for `BracketMethod::Tait`, whose total is not
+/// known in advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub done: u64,
+    pub total: Option<u64>,
+}
+
+/// The error returned when a computation is stopped before it finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputeError {
+    /// The cancellation token was cancelled.
+    Cancelled(Progress),
+    /// The time limit ran out.
+    TimedOut(Progress),
+    /// The diagram has more states than the state limit allows.
+    TooManyStates { states: u64, limit: u64 },
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let (reason, progress) = match self {
+            ComputeError::Cancelled(p) => ("cancelled", p),
+            ComputeError::TimedOut(p) => ("timed out", p),
+            ComputeError::TooManyStates { states, limit } => {
+                return write!(
+                    f,
+                    "the diagram has {} states, more than the limit of {}",
+                    states, limit
+                )
+            }
+        };
+        match progress.total {
+            Some(total) => write!(f, "{} after {} of {} steps", reason, progress.done, total),
+            None => write!(f, "{} after {} steps", reason, progress.done),
+        }
+    }
+}
+
+impl Error for ComputeError {}
+
+/// Tracks a running computation against its options.
+pub(crate) struct Monitor<'a> {
+    options: &'a ComputeOptions,
+    started: Instant,
+    done: AtomicU64,
+    total: Option<u64>,
+}
+
+impl<'a> Monitor<'a> {
+    pub(crate) fn new(options: &'a ComputeOptions, total: Option<u64>) -> Self {
+        Monitor {
+            options,
+            started: Instant::now(),
+            done: AtomicU64::new(0),
+            total,
+        }
+    }
+
+    /// Returns an error if the computation should stop.
+    pub(crate) fn check(&self) -> Result<(), ComputeError> {
+        if let Some(token) = &self.options.cancellation {
+            if token.is_cancelled() {
+                return Err(ComputeError::Cancelled(self.progress()));
+            }
+        }
+        if let Some(limit) = self.options.time_limit {
+            if self.started.elapsed() >= limit {
+                return Err(ComputeError::TimedOut(self.progress()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Records that some more of the work is done, and reports the progress.
+    pub(crate) fn advance(&self, units: u64) {
+        let done = self.done.fetch_add(units, Ordering::Relaxed) + units;
+        if let Some(report) = &self.options.progress {
+            report(Progress {
+                done,
+                total: self.total,
+            });
+        }
+    }
+
+    pub(crate) fn progress(&self) -> Progress {
+        Progress {
+            done: self.done.load(Ordering::Relaxed),
+            total: self.total,
+        }
+    }
+}
+
+/// Checks the state limit of the options against a number of states.
+pub(crate) fn check_states(options: &ComputeOptions, states: u64) -> Result<(), ComputeError> {
+    match options.state_limit {
+        Some(limit) if states > limit => Err(ComputeError::TooManyStates { states, limit }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CancellationToken, ComputeError, ComputeOptions, Progress};
+    use crate::{BracketMethod, Knot};
+    use std::{
+        str::FromStr,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    #[test]
+    fn limits() {
+        let knot = Knot::from_str("abAB").unwrap();
+        assert_eq!(
+            knot.try_bracket_polynomial(&ComputeOptions::new().state_limit(16)),
+            Ok(knot.bracket_polynomial())
+        );
+        assert_eq!(
+            knot.try_bracket_polynomial(&ComputeOptions::new().state_limit(15)),
+            Err(ComputeError::TooManyStates {
+                states: 16,
+                limit: 15
+            })
+        );
+
+        let token = CancellationToken::new();
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        for &method in &[
+            BracketMethod::StateSum,
+            BracketMethod::Tait,
+            BracketMethod::Decomposition,
+        ] {
+            let options = ComputeOptions::new()
+                .method(method)
+                .cancellation(token.clone());
+            match knot.try_jones_polynomial(&options) {
+                Err(ComputeError::Cancelled(Progress { done: 0, .. })) => {}
+                other => panic!("{:?}: {:?}", method, other),
+            }
+
+            let options = ComputeOptions::new()
+                .method(method)
+                .time_limit(Duration::from_secs(0));
+            match knot.try_beta_polynomial(&options) {
+                Err(ComputeError::TimedOut(_)) => {}
+                other => panic!("{:?}: {:?}", method, other),
+            }
+        }
+        assert_eq!(
+            knot.try_resolutions(&ComputeOptions::new().cancellation(token))
+                .map(|r| r.len()),
+            Err(ComputeError::Cancelled(Progress {
+                done: 0,
+                total: Some(16)
+            }))
+        );
+    }
+
+    #[test]
+    fn progress() {
+        let knot = Knot::from_str(&"abAB".repeat(4)).unwrap();
+        let done = Arc::new(AtomicU64::new(0));
+        let seen = done.clone();
+        let options = ComputeOptions::new().on_progress(move |p| {
+            assert_eq!(p.total, Some(1 << 16));
+            seen.fetch_max(p.done, Ordering::Relaxed);
+        });
+        assert_eq!(
+            knot.try_state_histogram(&options),
+            Ok(knot.state_histogram())
+        );
+        assert_eq!(done.load(Ordering::Relaxed), 1 << 16);
+
+        let steps = Arc::new(AtomicU64::new(0));
+        let seen = steps.clone();
+        let options = ComputeOptions::new()
+            .method(BracketMethod::Decomposition)
+            .on_progress(move |p| {
+                assert_eq!(p.total, Some(16));
+                seen.fetch_add(1, Ordering::Relaxed);
+            });
+        assert_eq!(
+            knot.try_bracket_polynomial(&options),
+            Ok(knot.bracket_polynomial())
+        );
+        assert_eq!(steps.load(Ordering::Relaxed), 16);
+        assert_eq!(
+            knot.try_resolutions(&ComputeOptions::new()),
+            Ok(knot.resolutions())
+        );
+    }
+
+    #[test]
+    fn threads() {
+        let knot = Knot::from_str(&"abAB".repeat(4)).unwrap();
+        let expected = knot.state_histogram();
+
+        // Sequential computations stay on this thread and report their chunks in order.
+        let caller = thread::current().id();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let options = ComputeOptions::new().sequential().on_progress(move |p| {
+            assert_eq!(thread::current().id(), caller);
+            seen.lock().unwrap().push(p.done);
+        });
+        assert_eq!(knot.try_state_histogram(&options), Ok(expected.clone()));
+        let chunks: Vec<u64> = (1..=16).map(|k| k << 12).collect();
+        assert_eq!(*reports.lock().unwrap(), chunks);
+
+        #[cfg(feature = "rayon")]
+        {
+            let pool = Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(2)
+                    .build()
+                    .unwrap(),
+            );
+            let workers = pool.clone();
+            let options = ComputeOptions::new()
+                .thread_pool(pool)
+                .on_progress(move |_| assert!(workers.current_thread_index().is_some()));
+            assert_eq!(knot.try_state_histogram(&options), Ok(expected));
+            assert_eq!(
+                knot.try_resolutions(&options.sequential_below(0)),
+                Ok(knot.resolutions())
+            );
+        }
+    }
+}
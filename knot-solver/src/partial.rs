@@ -0,0 +1,254 @@
+//! State sums split into ranges that can be computed separately and merged.
+//!
+//! The `2^n` states of a diagram are numbered by their position in the Gray-code order used by
+//! `Knot::state_histogram`. `Knot::partial_bracket` counts the states in one range of positions,
+//! and the partial results for ranges that together cover every position are merged into the
+//! full histogram, from which the invariants follow.
+
+use crate::{
+    beta_from_bracket, cache::CacheKey, jones_from_beta, polynomial::Polynomial, ComputeError,
+    ComputeOptions, Knot, StateHistogram,
+};
+use std::{error::Error, fmt, iter, ops::Range};
+
+impl Knot {
+    /// Returns the `index`th of `count` ranges of states of nearly equal size, which together
+    /// cover every state of the diagram.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `count`.
+    pub fn shard_range(&self, index: u128, count: u128) -> Range<u128> {
+        assert!(index < count, "shard {} of {} does not exist", index, count);
+        let total = 2u128.pow(self.num_crossings() as u32);
+        let (size, extra) = (total / count, total % count);
+        let start = index * size + index.min(extra);
+        let end = start + size + if index < extra { 1 } else { 0 };
+        start..end
+    }
+
+    /// Counts the states at the given positions of the Gray-code order.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let knot = Knot::from_str("abAB").unwrap();
+    /// let first = knot.partial_bracket(0..5);
+    /// let rest = knot.partial_bracket(5..16);
+    /// let merged = first.merge(rest).unwrap();
+    /// assert_eq!(merged.bracket_polynomial(), Some(knot.bracket_polynomial()));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range goes past the last state.
+    pub fn partial_bracket(&self, range: Range<u128>) -> PartialBracket {
+        self.try_partial_bracket(range, &ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Like `partial_bracket`, but checks the given options while counting. The state limit of
+    /// the options applies to the size of the range.
+    pub fn try_partial_bracket(
+        &self,
+        range: Range<u128>,
+        options: &ComputeOptions,
+    ) -> Result<PartialBracket, ComputeError> {
+        assert!(
+            range.end <= 2u128.pow(self.num_crossings() as u32),
+            "the diagram has no state at position {}",
+            range.end - 1
+        );
+        let histogram = self.range_histogram(range.clone(), options)?;
+        let mut ranges = Vec::new();
+        if range.start < range.end {
+            ranges.push(range);
+        }
+        Ok(PartialBracket {
+            diagram: CacheKey::diagram(self).as_str().to_string(),
+            writhe: self.writhe(),
+            ranges,
+            histogram,
+        })
+    }
+}
+
+/// The counted states of a diagram in some ranges of the Gray-code order, produced by
+/// `Knot::partial_bracket`.
+///
+/// With the `serde` feature, partial results can be written out by separate processes and read
+/// back to be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartialBracket {
+    /// The diagram the states belong to, as given by `CacheKey::diagram`.
+    diagram: String,
+    writhe: isize,
+    /// The ranges of states counted, sorted and with neighbouring ranges joined.
+    ranges: Vec<Range<u128>>,
+    histogram: StateHistogram,
+}
+
+impl PartialBracket {
+    /// Combines two partial results for the same diagram that do not share any states.
+    pub fn merge(mut self, other: PartialBracket) -> Result<PartialBracket, MergeError> {
+        if self.diagram != other.diagram
+            || self.histogram.crossings() != other.histogram.crossings()
+        {
+            return Err(MergeError::DifferentDiagrams);
+        }
+        let mut ranges = self.ranges;
+        ranges.extend(other.ranges);
+        ranges.sort_by_key(|r| r.start);
+
+        let mut joined: Vec<Range<u128>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match joined.last_mut() {
+                Some(last) if range.start < last.end => {
+                    return Err(MergeError::Overlapping(
+                        range.start..last.end.min(range.end),
+                    ))
+                }
+                Some(last) if range.start == last.end => last.end = range.end,
+                _ => joined.push(range),
+            }
+        }
+
+        self.ranges = joined;
+        self.histogram = self.histogram.merge(other.histogram);
+        Ok(self)
+    }
+
+    /// Returns the ranges of states that have been counted.
+    pub fn ranges(&self) -> &[Range<u128>] {
+        &self.ranges
+    }
+
+    /// Returns the ranges of states that have not been counted yet.
+    pub fn missing(&self) -> Vec<Range<u128>> {
+        let total = 2u128.pow(self.histogram.crossings() as u32);
+        let mut missing = Vec::new();
+        let mut next = 0;
+        for range in self.ranges.iter().cloned().chain(iter::once(total..total)) {
+            if range.start > next {
+                missing.push(next..range.start);
+            }
+            next = range.end;
+        }
+        missing
+    }
+
+    /// Returns whether every state of the diagram has been counted.
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// Returns the states counted so far.
+    pub fn histogram(&self) -> &StateHistogram {
+        &self.histogram
+    }
+
+    /// Returns the bracket polynomial, or `None` if some states have not been counted.
+    pub fn bracket_polynomial(&self) -> Option<Polynomial> {
+        if self.is_complete() {
+            Some(self.histogram.bracket_polynomial())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the beta polynomial, or `None` if some states have not been counted.
+    pub fn beta_polynomial(&self) -> Option<Polynomial> {
+        self.bracket_polynomial()
+            .map(|bracket| beta_from_bracket(bracket, self.writhe))
+    }
+
+    /// Returns the jones polynomial, or `None` if some states have not been counted.
+    pub fn jones_polynomial(&self) -> Option<Polynomial> {
+        self.beta_polynomial().map(jones_from_beta)
+    }
+}
+
+/// The reasons that two `PartialBracket`s cannot be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// The results are for different diagrams.
+    DifferentDiagrams,
+    /// Both results counted the states in this range.
+    Overlapping(Range<u128>),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            MergeError::DifferentDiagrams => write!(f, "the results are for different diagrams"),
+            MergeError::Overlapping(r) => {
+                write!(f, "states {} to {} were counted twice", r.start, r.end - 1)
+            }
+        }
+    }
+}
+
+impl Error for MergeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::MergeError;
+    use crate::Knot;
+    use std::str::FromStr;
+
+    #[test]
+    fn shards() {
+        let knot = Knot::from_str("abAB").unwrap();
+        let ranges: Vec<_> = (0..3).map(|i| knot.shard_range(i, 3)).collect();
+        assert_eq!(ranges, vec![0..6, 6..11, 11..16]);
+        assert_eq!(knot.shard_range(0, 1), 0..16);
+        // More shards than states leaves some of them empty.
+        assert_eq!(knot.shard_range(20, 32), 16..16);
+
+        let mut partials: Vec<_> = ranges
+            .into_iter()
+            .map(|r| knot.partial_bracket(r))
+            .collect();
+        let last = partials.pop().unwrap();
+        let first = partials.remove(0);
+        let outer = first.merge(last).unwrap();
+        assert_eq!(outer.ranges(), &[0..6, 11..16]);
+        assert_eq!(outer.missing(), vec![6..11]);
+        assert_eq!(outer.bracket_polynomial(), None);
+
+        let full = outer.clone().merge(partials.remove(0)).unwrap();
+        assert!(full.is_complete());
+        assert_eq!(full.histogram(), &knot.state_histogram());
+        assert_eq!(full.jones_polynomial(), Some(knot.jones_polynomial()));
+        assert_eq!(
+            full.clone().merge(knot.partial_bracket(16..16)),
+            Ok(full.clone())
+        );
+
+        assert_eq!(
+            outer.merge(knot.partial_bracket(3..8)),
+            Err(MergeError::Overlapping(3..6))
+        );
+        assert_eq!(
+            full.merge(Knot::from_str("abAb").unwrap().partial_bracket(0..1)),
+            Err(MergeError::DifferentDiagrams)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization() {
+        let knot = Knot::from_str(&"aBc".repeat(4)).unwrap();
+        let range = knot.shard_range(1, 4);
+        let json = serde_json::to_string(&knot.partial_bracket(range)).unwrap();
+        let partial: super::PartialBracket = serde_json::from_str(&json).unwrap();
+        assert_eq!(partial.missing(), vec![0..1024, 2048..4096]);
+        assert_eq!(partial.histogram().total(), 1024);
+
+        // Rows of counts must have room for every smoothing difference.
+        let bad = json.replace(r#""crossings":12"#, r#""crossings":13"#);
+        assert!(serde_json::from_str::<super::PartialBracket>(&bad).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Knot diagrams given by planar diagram (PD) codes.
+//!
+//! A PD code numbers the edges of a diagram, the arcs between neighbouring crossings, so that the
+//! numbers increase along each component. Every crossing is given by the four edges around it,
+//! counterclockwise, starting with the edge where the under-strand comes in. This is the
+//! convention of the Knot Atlas and KnotInfo.
+
+use crate::{Crossing, Knot, KnotParseError, Orientation, RegionCounter};
+use std::collections::BTreeMap;
+
+impl Knot {
+    /// Creates a knot from a planar diagram code.
+    ///
+    /// Edges can have any numbers, as long as every number appears exactly twice. Each component
+    /// is followed from the first edge of a crossing where it goes under, so the direction of
+    /// every strand through every crossing is known. A crossing `[i, j, k, l]` is positive when
+    /// the over-strand goes from `l` to `j`. A component that never goes under another one runs
+    /// the way its numbers increase, which is all the code says about it.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let figure_eight =
+    ///     Knot::from_pd(&[[4, 2, 5, 1], [8, 6, 1, 5], [6, 3, 7, 4], [2, 7, 3, 8]]).unwrap();
+    /// assert_eq!(
+    ///     figure_eight.jones_polynomial(),
+    ///     Knot::from_str("aBaB").unwrap().jones_polynomial()
+    /// );
+    /// assert!(Knot::from_pd(&[[1, 2, 3, 4]]).is_err());
+    /// ```
+    pub fn from_pd(code: &[[usize; 4]]) -> Result<Knot, KnotParseError> {
+        let ends = edge_ends(code)?;
+        let positive = signs(code, &ends)?;
+        Knot::from_ends(code, &ends, &positive)
+    }
+
+    /// Creates a knot from a planar diagram code whose crossing signs are already known, with
+    /// `true` for positive crossings.
+    pub(crate) fn from_signed_pd(
+        code: &[[usize; 4]],
+        positive: &[bool],
+    ) -> Result<Knot, KnotParseError> {
+        let ends = edge_ends(code)?;
+        Knot::from_ends(code, &ends, positive)
+    }
+
+    /// Creates a knot from a planar diagram code, the ends of its edges and the signs of its
+    /// crossings.
+    fn from_ends(
+        code: &[[usize; 4]],
+        ends: &Ends,
+        positive: &[bool],
+    ) -> Result<Knot, KnotParseError> {
+        if code.is_empty() {
+            return Ok(Knot {
+                crossings: Vec::new(),
+                region_num: 2,
+            });
+        }
+
+        // Corner `4c + p` of crossing `c` lies between the edges at positions `p` and `p + 1`.
+        // Following the edge at `p + 1` to its other end, the same region lies just
+        // counterclockwise of it there.
+        let mut regions = RegionCounter::new(4 * code.len());
+        let mut pieces = RegionCounter::new(code.len());
+        for c in 0..code.len() {
+            for p in 0..4 {
+                let (d, q) = other_end(code, ends, c, (p + 1) % 4);
+                regions.combine(4 * c + p, 4 * d + q);
+                pieces.combine(c, d);
+            }
+        }
+        // A planar piece with `n` crossings has `n + 2` regions. Separate pieces share their
+        // outer region, which can be any of their regions.
+        let separate = pieces.current_count();
+        for c in 1..code.len() {
+            if pieces.find(c) != pieces.find(0) {
+                regions.combine(4 * c, 0);
+                pieces.combine(c, 0);
+            }
+        }
+        if regions.current_count() != code.len() + separate + 1 {
+            return invalid("the diagram is not planar".to_string());
+        }
+
+        let mut number = BTreeMap::new();
+        let mut region = |corner: usize| {
+            let root = regions.find(corner);
+            let next = number.len();
+            *number.entry(root).or_insert(next)
+        };
+        let crossings = positive
+            .iter()
+            .enumerate()
+            .map(|(c, &positive)| {
+                // The 0 resolution joins the corners that the over-strand sweeps through when
+                // it turns counterclockwise.
+                let joined = (region(4 * c + 1), region(4 * c + 3));
+                let apart = (region(4 * c), region(4 * c + 2));
+                if positive {
+                    Crossing {
+                        top: joined.0,
+                        bottom: joined.1,
+                        left: apart.0,
+                        right: apart.1,
+                        orientation: Orientation::Positive,
+                    }
+                } else {
+                    Crossing {
+                        top: apart.0,
+                        bottom: apart.1,
+                        left: joined.0,
+                        right: joined.1,
+                        orientation: Orientation::Negative,
+                    }
+                }
+            })
+            .collect();
+        Ok(Knot {
+            crossings,
+            region_num: regions.current_count(),
+        })
+    }
+}
+
+/// The crossings and positions around them where each edge ends.
+type Ends = BTreeMap<usize, Vec<(usize, usize)>>;
+
+fn invalid<T>(reason: String) -> Result<T, KnotParseError> {
+    Err(KnotParseError::InvalidPlanarDiagram(reason))
+}
+
+/// Finds where each edge ends, checking that every edge has two ends.
+fn edge_ends(code: &[[usize; 4]]) -> Result<Ends, KnotParseError> {
+    let mut ends = Ends::new();
+    for (c, edges) in code.iter().enumerate() {
+        for (p, &e) in edges.iter().enumerate() {
+            ends.entry(e).or_default().push((c, p));
+        }
+    }
+    match ends.iter().find(|(_, ends)| ends.len() != 2) {
+        Some((e, _)) => invalid(format!("edge {} does not appear exactly twice", e)),
+        None => Ok(ends),
+    }
+}
+
+/// Returns the crossing and position at the other end of the edge at a position.
+fn other_end(code: &[[usize; 4]], ends: &Ends, c: usize, p: usize) -> (usize, usize) {
+    *ends[&code[c][p]]
+        .iter()
+        .find(|&&end| end != (c, p))
+        .expect("every edge has two ends")
+}
+
+/// Follows the strands through every crossing to find its sign.
+fn signs(code: &[[usize; 4]], ends: &Ends) -> Result<Vec<bool>, KnotParseError> {
+    // Whether the strand at each position comes into the crossing there, once it is known.
+    let mut incoming = vec![[None; 4]; code.len()];
+    for c in 0..code.len() {
+        follow(code, ends, &mut incoming, c, 0)?;
+    }
+    for (c, &[_, j, _, l]) in code.iter().enumerate() {
+        if incoming[c][1].is_none() {
+            let start = if j == l + 1 || l > j + 1 { 3 } else { 1 };
+            follow(code, ends, &mut incoming, c, start)?;
+        }
+    }
+    Ok(incoming.iter().map(|c| c[3] == Some(true)).collect())
+}
+
+/// Follows the strand that comes into a crossing at a position around its component, until it
+/// gets back to a part whose direction is known.
+fn follow(
+    code: &[[usize; 4]],
+    ends: &Ends,
+    incoming: &mut [[Option<bool>; 4]],
+    mut c: usize,
+    mut p: usize,
+) -> Result<(), KnotParseError> {
+    loop {
+        match incoming[c][p] {
+            Some(true) => return Ok(()),
+            Some(false) => {
+                return invalid(format!("a component goes both ways through crossing {}", c))
+            }
+            None => {}
+        }
+        let out = (p + 2) % 4;
+        incoming[c][p] = Some(true);
+        incoming[c][out] = Some(false);
+        let (d, q) = other_end(code, ends, c, out);
+        c = d;
+        p = q;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Knot, KnotParseError};
+    use std::str::FromStr;
+
+    #[test]
+    fn pd_codes() {
+        let trefoil = Knot::from_pd(&[[1, 5, 2, 4], [3, 1, 4, 6], [5, 3, 6, 2]]).unwrap();
+        assert_eq!(trefoil.num_regions(), 5);
+        assert_eq!(trefoil.writhe(), 3);
+        assert_eq!(
+            trefoil.bracket_polynomial(),
+            Knot::from_str("aaa").unwrap().bracket_polynomial()
+        );
+
+        // A Hopf link with negative crossings, and a kink in an unknot.
+        let hopf = Knot::from_pd(&[[4, 1, 3, 2], [2, 3, 1, 4]]).unwrap();
+        assert_eq!(
+            hopf.jones_polynomial(),
+            Knot::from_str("AA").unwrap().jones_polynomial()
+        );
+        let kink = Knot::from_pd(&[[1, 1, 2, 2]]).unwrap();
+        assert_eq!(kink.writhe(), 1);
+        assert_eq!(kink.beta_polynomial(), "1".parse().unwrap());
+
+        // Two pieces side by side.
+        let split = Knot::from_pd(&[[1, 1, 2, 2], [3, 3, 4, 4]]).unwrap();
+        assert_eq!(split.num_regions(), 5);
+        assert_eq!(Knot::from_pd(&[]).unwrap().num_regions(), 2);
+
+        // The numbers of the edges only matter for components that never go under.
+        for code in &[
+            vec![[10, 50, 20, 40], [30, 10, 40, 60], [50, 30, 60, 20]],
+            vec![[6, 2, 5, 3], [4, 6, 3, 1], [2, 4, 1, 5]],
+        ] {
+            let knot = Knot::from_pd(code).unwrap();
+            assert_eq!(knot.writhe(), 3);
+            assert_eq!(knot.jones_polynomial(), trefoil.jones_polynomial());
+        }
+        let hopf = Knot::from_pd(&[[40, 10, 30, 20], [20, 30, 10, 40]]).unwrap();
+        assert_eq!(hopf.writhe(), -2);
+
+        for code in &[
+            vec![[1, 2, 3, 4]],
+            vec![[1, 1, 1, 2]],
+            vec![[1, 2, 3, 4], [1, 2, 3, 4]],
+            // The first crossing turned around, so that the knot goes under it backwards.
+            vec![[2, 4, 1, 5], [3, 1, 4, 6], [5, 3, 6, 2]],
+        ] {
+            match Knot::from_pd(code) {
+                Err(KnotParseError::InvalidPlanarDiagram(_)) => {}
+                other => panic!("{:?} gave {:?}", code, other),
+            }
+        }
+    }
+}
@@ -1,8 +1,9 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::cmp::Ordering;
 use std::fmt;
 use std::iter::Sum;
 use std::ops::{Add, AddAssign, Mul};
-use rayon::prelude::*;
 
 use num::{rational::Rational, One, Zero};
 
@@ -22,23 +23,81 @@ macro_rules! term {
     };
 }
 
-// macro_rules! polynomial {
-//     ($c:literal ^ $e:literal $($tail:tt)*) => {
-//         $crate::polynomial::polynomial::from($crate::polynomial::Term::new($c, $e)) + polynomial!($($tail)*)
-//     };
-//     ($c:literal ^ )
-// }
+/// Parses a `Polynomial` from a string literal, panicking if it is malformed.
+#[macro_export]
+macro_rules! polynomial {
+    ($s:expr) => {
+        $s.parse::<$crate::polynomial::Polynomial>()
+            .expect("invalid polynomial literal")
+    };
+}
 
-/// A polynomial with one variable, represented by `A` in this documentation..
-#[derive(Eq, PartialEq, Debug)]
-pub struct Polynomial(Vec<Term>);
+mod eval;
+mod format;
+mod parse;
+
+pub use self::eval::{Cyclotomic, EvalError, EvaluationPoint, RootOfUnity};
+pub use self::format::{Format, Formatted};
+pub use self::parse::{PolynomialParseError, PolynomialParseErrorKind};
+
+/// A polynomial with one variable, represented by `A` in this documentation.
+///
+/// The variable defaults to `A`. Polynomials in different variables are different, except for
+/// constants, which are the same in every variable. Adding or multiplying polynomials in
+/// different variables panics unless one of them is a constant, which takes on the variable of
+/// the other.
+#[derive(Eq, Debug, Clone)]
+pub struct Polynomial {
+    terms: Vec<Term>,
+    variable: char,
+}
 
 impl Polynomial {
-    /// Creates a polynomial from a list of `Term`s.
+    /// Creates a polynomial in `A` from a list of `Term`s.
     pub fn from_vec(mut terms: Vec<Term>) -> Self {
         terms.retain(|t| !t.is_zero());
         terms.sort_unstable_by(Term::compare_exponent);
-        Polynomial(terms)
+        Polynomial {
+            terms,
+            variable: 'A',
+        }
+    }
+
+    /// Returns the name of the polynomial's variable.
+    pub fn variable(&self) -> char {
+        self.variable
+    }
+
+    /// Renames the polynomial's variable without changing any of its terms.
+    pub fn with_variable(mut self, variable: char) -> Self {
+        self.variable = variable;
+        self
+    }
+
+    /// Replaces the variable with another polynomial, which may be in a different variable.
+    ///
+    /// Any polynomial can be substituted into terms with nonnegative integer exponents. Negative
+    /// and fractional exponents are only supported when the replacement is a single term, and a
+    /// fractional power of its coefficient must itself be rational.
+    ///
+    /// ```
+    /// use knot_solver::polynomial::Polynomial;
+    ///
+    /// let p: Polynomial = "A^-8 + A^4".parse().unwrap();
+    /// let q: Polynomial = "t^(-1/4)".parse().unwrap();
+    /// assert_eq!(p.substitute(&q).unwrap().to_string(), "1t^2 + 1t^-1");
+    /// ```
+    pub fn substitute(&self, replacement: &Polynomial) -> Result<Polynomial, EvalError> {
+        self.eval(replacement)
+            .map(|p| p.with_variable(replacement.variable))
+    }
+
+    /// Raises the polynomial to a nonnegative integer power.
+    pub fn pow(&self, exponent: u32) -> Polynomial {
+        (0..exponent).fold(
+            Polynomial::from(Term::one()).with_variable(self.variable),
+            |acc, _| acc * self.clone(),
+        )
     }
 
     /// Creates a polynomial that is equal to 0.
@@ -48,25 +107,51 @@ impl Polynomial {
 
     /// Returns a `DoubleEndedIterator` over the terms of the polynomials.
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Term> + '_ {
-        self.0.iter()
+        self.terms.iter()
+    }
+
+    /// Returns whether the polynomial has no terms with a nonzero exponent.
+    fn is_constant(&self) -> bool {
+        self.terms.iter().all(|t| t.exponent.is_zero())
+    }
+
+    /// Returns the variable of a sum or product of two polynomials.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomials are in different variables and neither is a constant.
+    fn common_variable(&self, other: &Polynomial) -> char {
+        if self.terms.is_empty() || (self.is_constant() && !other.is_constant()) {
+            other.variable
+        } else {
+            assert!(
+                other.is_constant() || self.variable == other.variable,
+                "cannot combine polynomials in {} and {}",
+                self.variable,
+                other.variable
+            );
+            self.variable
+        }
     }
 
     /// Removes all of the `Term`s equal to zero from the polynomial.
     pub fn remove_zero_terms(&mut self) {
-        self.0.retain(|t| !t.is_zero());
+        self.terms.retain(|t| !t.is_zero());
     }
 
+    #[cfg(feature = "rayon")]
     pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = &Term> + '_ {
-        self.0.par_iter()
+        self.terms.par_iter()
     }
 }
 
+#[cfg(feature = "rayon")]
 impl IntoParallelIterator for Polynomial {
     type Iter = <Vec<Term> as IntoParallelIterator>::Iter;
     type Item = Term;
 
     fn into_par_iter(self) -> Self::Iter {
-        self.0.into_par_iter()
+        self.terms.into_par_iter()
     }
 }
 
@@ -90,7 +175,7 @@ impl IntoIterator for Polynomial {
     type IntoIter = <Vec<Term> as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.terms.into_iter()
     }
 }
 
@@ -99,7 +184,7 @@ impl fmt::Display for Polynomial {
         let terms = self
             .iter()
             .rev()
-            .map(|t| format!("{}", t))
+            .map(|t| format!("{}{}^{}", t.coefficient, self.variable, t.exponent))
             .collect::<Vec<_>>()
             .join(" + ");
         write!(f, "{}", terms)
@@ -119,11 +204,11 @@ impl Add<Term> for Polynomial {
 impl AddAssign<Term> for Polynomial {
     fn add_assign(&mut self, rhs: Term) {
         if !rhs.is_zero() {
-            match self.0.binary_search_by(|t| t.compare_exponent(&rhs)) {
+            match self.terms.binary_search_by(|t| t.compare_exponent(&rhs)) {
                 Ok(i) => {
-                    let old = self.0.remove(i);
+                    let old = self.terms.remove(i);
                     if !(old.coefficient + rhs.coefficient).is_zero() {
-                        self.0.insert(
+                        self.terms.insert(
                             i,
                             Term {
                                 // Need to manually construct term since adding terms returns a polynomial.
@@ -133,7 +218,7 @@ impl AddAssign<Term> for Polynomial {
                         );
                     }
                 }
-                Err(i) => self.0.insert(i, rhs),
+                Err(i) => self.terms.insert(i, rhs),
             }
         }
     }
@@ -154,26 +239,60 @@ impl Add for Polynomial {
 
     fn add(self, rhs: Polynomial) -> Self::Output {
         let mut p = self;
-        for term in rhs {
-            p += term;
-        }
+        p += rhs;
         p
     }
 }
 
 impl AddAssign for Polynomial {
+    /// Adds the terms of `rhs`, which must be in the same variable unless one of the two is a
+    /// constant.
     fn add_assign(&mut self, rhs: Polynomial) {
+        self.variable = self.common_variable(&rhs);
         for term in rhs {
             *self += term;
         }
     }
 }
 
+impl PartialEq for Polynomial {
+    /// Polynomials are equal when they have the same terms in the same variable. Constants
+    /// don't depend on the variable, so they are the same in every variable.
+    fn eq(&self, other: &Self) -> bool {
+        self.terms == other.terms && (self.variable == other.variable || self.is_constant())
+    }
+}
+
+impl Zero for Polynomial {
+    fn zero() -> Self {
+        Polynomial::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
 impl Mul<Term> for Polynomial {
     type Output = Polynomial;
 
     fn mul(self, rhs: Term) -> Self::Output {
-        Polynomial::from_vec(self.into_iter().map(|t| t * rhs).collect())
+        let variable = self.variable;
+        Polynomial::from_vec(self.into_iter().map(|t| t * rhs).collect()).with_variable(variable)
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Polynomial) -> Self::Output {
+        let mut p = Polynomial::zero().with_variable(self.common_variable(&rhs));
+        for t in self.iter() {
+            for u in rhs.iter() {
+                p += *t * *u;
+            }
+        }
+        p
     }
 }
 
@@ -483,6 +602,7 @@ mod tests {
 
     mod polynomial {
         use crate::polynomial::{Polynomial, Term};
+        use num::Zero;
 
         #[test]
         fn display() {
@@ -528,6 +648,39 @@ mod tests {
             );
         }
 
+        #[test]
+        fn zero() {
+            let zero = Polynomial::zero().with_variable('t');
+            assert!(zero.is_zero());
+            assert_eq!(zero, Polynomial::zero());
+            assert_eq!(
+                Polynomial::from(Term::new(1, 2)).with_variable('t') + Term::new(-1, 2),
+                Polynomial::zero()
+            );
+            assert_ne!(
+                Polynomial::from(Term::new(1, 2)).with_variable('t'),
+                Polynomial::from(Term::new(1, 2))
+            );
+        }
+
+        #[test]
+        fn variables() {
+            let t: Polynomial = "t^2 + 1".parse().unwrap();
+            let three = Polynomial::from(Term::new(3, 0));
+            assert_eq!(three, three.clone().with_variable('q'));
+            assert_eq!((t.clone() + three.clone()).variable(), 't');
+            assert_eq!((three.clone() + t.clone()).variable(), 't');
+            assert_eq!((three.clone() * t.clone()).variable(), 't');
+            assert_eq!((t.clone() * three).to_string(), "3t^2 + 3t^0");
+        }
+
+        #[test]
+        #[should_panic(expected = "cannot combine polynomials in t and A")]
+        fn mixed_variables() {
+            let t: Polynomial = "t^2 + 1".parse().unwrap();
+            let _ = t + Polynomial::from(Term::new(1, 1));
+        }
+
         #[test]
         fn add_polynomial() {
             assert_eq!(
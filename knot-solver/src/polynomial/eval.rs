@@ -0,0 +1,645 @@
+use super::{Polynomial, Term};
+use std::{
+    error::Error,
+    f64::consts::PI,
+    fmt,
+    ops::{Add, Mul, Neg},
+};
+
+use num::{
+    checked_pow,
+    complex::Complex64,
+    integer::{lcm, Roots},
+    rational::Rational,
+    CheckedAdd, CheckedMul, One, Signed, ToPrimitive, Zero,
+};
+
+/// A value a `Polynomial` can be evaluated at.
+///
+/// Implementations decide how to interpret a single term `c·x^e` at the point; `Polynomial::eval`
+/// then sums the results with `add`.
+pub trait EvaluationPoint {
+    /// The type of the values produced by the evaluation.
+    type Output: Zero + Add<Output = Self::Output>;
+
+    /// Evaluates `coefficient · self^exponent`.
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<Self::Output, EvalError>;
+
+    /// Adds the value of a term to the sum of the ones before it. Output types that can
+    /// overflow should check for it here.
+    fn add(sum: Self::Output, term: Self::Output) -> Result<Self::Output, EvalError> {
+        Ok(sum + term)
+    }
+}
+
+/// The reasons evaluating a `Polynomial` can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// A term's value cannot be represented in the output type, such as `2^(1/2)` as an integer.
+    NotRepresentable,
+    /// A negative power of zero was taken.
+    DivisionByZero,
+    /// The value does not fit in the output type.
+    Overflow,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            EvalError::NotRepresentable => write!(f, "value is not representable"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+impl Polynomial {
+    /// Evaluates the polynomial at the given point.
+    ///
+    /// ```
+    /// use knot_solver::polynomial::Polynomial;
+    ///
+    /// let jones: Polynomial = "t + t^3 - t^4".parse().unwrap();
+    /// assert_eq!(jones.eval(-1isize), Ok(-3));
+    /// ```
+    pub fn eval<T: EvaluationPoint>(&self, at: T) -> Result<T::Output, EvalError> {
+        self.iter().try_fold(T::Output::zero(), |sum, t| {
+            T::add(sum, at.term(t.coefficient(), t.exponent())?)
+        })
+    }
+}
+
+/// Returns the exponent as an `i32` if it is an integer.
+fn integer_exponent(exponent: Rational) -> Result<i32, EvalError> {
+    if exponent.is_integer() {
+        exponent.to_integer().to_i32().ok_or(EvalError::Overflow)
+    } else {
+        Err(EvalError::NotRepresentable)
+    }
+}
+
+impl EvaluationPoint for isize {
+    type Output = isize;
+
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<isize, EvalError> {
+        let exponent = integer_exponent(exponent)?;
+        let power = if exponent >= 0 {
+            self.checked_pow(exponent as u32)
+                .ok_or(EvalError::Overflow)?
+        } else {
+            match *self {
+                0 => return Err(EvalError::DivisionByZero),
+                1 => 1,
+                -1 => (-1isize).pow(exponent.unsigned_abs() % 2),
+                _ => return Err(EvalError::NotRepresentable),
+            }
+        };
+
+        let value = coefficient
+            .checked_mul(&Rational::from_integer(power))
+            .ok_or(EvalError::Overflow)?;
+        if value.is_integer() {
+            Ok(value.to_integer())
+        } else {
+            Err(EvalError::NotRepresentable)
+        }
+    }
+
+    fn add(sum: isize, term: isize) -> Result<isize, EvalError> {
+        sum.checked_add(term).ok_or(EvalError::Overflow)
+    }
+}
+
+impl EvaluationPoint for Rational {
+    type Output = Rational;
+
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<Rational, EvalError> {
+        let exponent = integer_exponent(exponent)?;
+        let base = if exponent >= 0 {
+            *self
+        } else if self.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        } else {
+            self.recip()
+        };
+        checked_pow(base, exponent.unsigned_abs() as usize)
+            .and_then(|power| coefficient.checked_mul(&power))
+            .ok_or(EvalError::Overflow)
+    }
+
+    fn add(sum: Rational, term: Rational) -> Result<Rational, EvalError> {
+        sum.checked_add(&term).ok_or(EvalError::Overflow)
+    }
+}
+
+/// Converts a rational number to a float.
+fn to_f64(r: Rational) -> f64 {
+    *r.numer() as f64 / *r.denom() as f64
+}
+
+impl EvaluationPoint for f64 {
+    type Output = f64;
+
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<f64, EvalError> {
+        if exponent.is_negative() && self.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+
+        let power = if exponent.is_integer() {
+            self.powi(integer_exponent(exponent)?)
+        } else if self.is_sign_negative() {
+            return Err(EvalError::NotRepresentable);
+        } else {
+            self.powf(to_f64(exponent))
+        };
+        Ok(to_f64(coefficient) * power)
+    }
+}
+
+impl EvaluationPoint for Complex64 {
+    type Output = Complex64;
+
+    /// Fractional powers are taken on the principal branch.
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<Complex64, EvalError> {
+        if exponent.is_negative() && self.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+
+        let power = if exponent.is_integer() {
+            self.powi(integer_exponent(exponent)?)
+        } else {
+            self.powf(to_f64(exponent))
+        };
+        Ok(power * to_f64(coefficient))
+    }
+}
+
+impl EvaluationPoint for &Polynomial {
+    type Output = Polynomial;
+
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<Polynomial, EvalError> {
+        let scale = Term::new(coefficient, Rational::zero());
+        if exponent.is_integer() && !exponent.is_negative() {
+            let exponent = exponent.to_integer().to_u32().ok_or(EvalError::Overflow)?;
+            return Ok(self.pow(exponent) * scale);
+        }
+
+        match self.terms[..] {
+            [] => Err(EvalError::DivisionByZero),
+            [t] => {
+                let power = if exponent.is_integer() {
+                    t.coefficient.pow(integer_exponent(exponent)?)
+                } else {
+                    rational_power(t.coefficient, exponent).ok_or(EvalError::NotRepresentable)?
+                };
+                Ok(Polynomial::from(Term {
+                    coefficient: coefficient * power,
+                    exponent: t.exponent * exponent,
+                })
+                .with_variable(self.variable))
+            }
+            _ => Err(EvalError::NotRepresentable),
+        }
+    }
+}
+
+/// Returns `base^exponent` if `base` is positive and the result is rational.
+fn rational_power(base: Rational, exponent: Rational) -> Option<Rational> {
+    if !base.is_positive() {
+        return None;
+    }
+
+    let root = |n: isize| {
+        let r = n.nth_root(*exponent.denom() as u32);
+        if r.checked_pow(*exponent.denom() as u32) == Some(n) {
+            Some(r)
+        } else {
+            None
+        }
+    };
+    let root = Rational::new(root(*base.numer())?, root(*base.denom())?);
+    Some(root.pow(exponent.numer().to_i32()?))
+}
+
+/// The root of unity `e^(2πi·power/order)`, at which polynomials evaluate to exact `Cyclotomic`
+/// numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootOfUnity {
+    order: usize,
+    power: usize,
+}
+
+impl RootOfUnity {
+    /// Creates the root of unity `e^(2πi·power/order)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is zero.
+    pub fn new(order: usize, power: usize) -> Self {
+        assert!(order > 0, "roots of unity must have a positive order");
+        RootOfUnity {
+            order,
+            power: power % order,
+        }
+    }
+
+    /// Creates the primitive root of unity `e^(2πi/order)`.
+    pub fn primitive(order: usize) -> Self {
+        RootOfUnity::new(order, 1)
+    }
+}
+
+impl EvaluationPoint for RootOfUnity {
+    type Output = Cyclotomic;
+
+    /// Fractional powers are taken on the principal branch, so `(e^(2πi·k/n))^(p/q)` is
+    /// `e^(2πi·kp/(nq))` with `-n/2 < k <= n/2`.
+    fn term(&self, coefficient: Rational, exponent: Rational) -> Result<Cyclotomic, EvalError> {
+        let order = (self.order as isize)
+            .checked_mul(*exponent.denom())
+            .ok_or(EvalError::Overflow)?;
+        let angle = if 2 * self.power > self.order {
+            self.power as isize - self.order as isize
+        } else {
+            self.power as isize
+        };
+        let power = angle
+            .checked_mul(*exponent.numer())
+            .ok_or(EvalError::Overflow)?
+            .rem_euclid(order);
+        Ok(Cyclotomic::root_of_unity(order as usize, power as usize).scale(coefficient))
+    }
+}
+
+/// An exact element of the cyclotomic field `Q(ζ)`, where `ζ = e^(2πi/order)`.
+///
+/// The value is stored as rational coefficients of `1, ζ, ζ^2, ...` reduced modulo the
+/// `order`-th cyclotomic polynomial, so every element has exactly one representation for a
+/// given order.
+#[derive(Debug, Clone)]
+pub struct Cyclotomic {
+    order: usize,
+    coefficients: Vec<Rational>,
+}
+
+impl Cyclotomic {
+    /// Creates the rational number `r` as an element of `Q`.
+    pub fn from_rational(r: Rational) -> Self {
+        Cyclotomic {
+            order: 1,
+            coefficients: vec![r],
+        }
+    }
+
+    /// Creates `ζ^power` where `ζ = e^(2πi/order)`.
+    pub fn root_of_unity(order: usize, power: usize) -> Self {
+        let mut coefficients = vec![Rational::zero(); order];
+        coefficients[power % order] = Rational::one();
+        Cyclotomic::reduced(order, coefficients)
+    }
+
+    /// Returns the order of the root of unity `ζ` this element is expressed in.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the coefficients of `1, ζ, ζ^2, ...`.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// Returns the numeric value of this element.
+    pub fn to_complex(&self) -> Complex64 {
+        self.coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, c)| {
+                Complex64::from_polar(&1.0, &(2.0 * PI * k as f64 / self.order as f64)) * to_f64(*c)
+            })
+            .sum()
+    }
+
+    /// Returns the rational value of this element if it has one.
+    pub fn to_rational(&self) -> Option<Rational> {
+        if self.coefficients[1..].iter().all(Zero::is_zero) {
+            Some(self.coefficients[0])
+        } else {
+            None
+        }
+    }
+
+    /// Multiplies every coefficient by `r`.
+    fn scale(mut self, r: Rational) -> Self {
+        self.coefficients.iter_mut().for_each(|c| *c *= r);
+        self
+    }
+
+    /// Re-expresses this element in terms of `e^(2πi/order)`, where `order` is a multiple of
+    /// `self.order`.
+    fn lift(&self, order: usize) -> Self {
+        let step = order / self.order;
+        let mut coefficients = vec![Rational::zero(); order];
+        for (k, c) in self.coefficients.iter().enumerate() {
+            coefficients[k * step] = *c;
+        }
+        Cyclotomic::reduced(order, coefficients)
+    }
+
+    /// Reduces coefficients of powers of `ζ` modulo the cyclotomic polynomial of `order`.
+    fn reduced(order: usize, mut coefficients: Vec<Rational>) -> Self {
+        // First use ζ^order = 1 to bring every power below `order`.
+        for k in order..coefficients.len() {
+            let c = coefficients[k];
+            coefficients[k % order] += c;
+        }
+        coefficients.resize(order, Rational::zero());
+
+        let modulus = cyclotomic_polynomial(order);
+        let degree = modulus.len() - 1;
+        for k in (degree..order).rev() {
+            let c = coefficients[k];
+            if !c.is_zero() {
+                for (i, m) in modulus.iter().enumerate() {
+                    coefficients[k - degree + i] -= c * m;
+                }
+            }
+        }
+        coefficients.truncate(degree);
+
+        Cyclotomic {
+            order,
+            coefficients,
+        }
+    }
+}
+
+/// Returns the integer coefficients of the `n`-th cyclotomic polynomial, lowest degree first.
+fn cyclotomic_polynomial(n: usize) -> Vec<isize> {
+    // Φ_n = (x^n - 1) / ∏ Φ_d over the proper divisors d of n.
+    let mut quotient = vec![0; n + 1];
+    quotient[0] = -1;
+    quotient[n] = 1;
+
+    for d in (1..n).filter(|d| n.is_multiple_of(*d)) {
+        let divisor = cyclotomic_polynomial(d);
+        let degree = divisor.len() - 1;
+        let mut result = vec![0; quotient.len() - degree];
+        for k in (0..result.len()).rev() {
+            let c = quotient[k + degree];
+            result[k] = c;
+            for (i, m) in divisor.iter().enumerate() {
+                quotient[k + i] -= c * m;
+            }
+        }
+        quotient = result;
+    }
+
+    quotient
+}
+
+impl PartialEq for Cyclotomic {
+    fn eq(&self, other: &Self) -> bool {
+        let order = lcm(self.order, other.order);
+        self.lift(order).coefficients == other.lift(order).coefficients
+    }
+}
+
+impl Eq for Cyclotomic {}
+
+impl Add for Cyclotomic {
+    type Output = Cyclotomic;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let order = lcm(self.order, rhs.order);
+        let mut sum = self.lift(order);
+        for (c, d) in sum
+            .coefficients
+            .iter_mut()
+            .zip(rhs.lift(order).coefficients)
+        {
+            *c += d;
+        }
+        sum
+    }
+}
+
+impl Neg for Cyclotomic {
+    type Output = Cyclotomic;
+
+    fn neg(self) -> Self::Output {
+        self.scale(-Rational::one())
+    }
+}
+
+impl Mul for Cyclotomic {
+    type Output = Cyclotomic;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let order = lcm(self.order, rhs.order);
+        let (a, b) = (self.lift(order), rhs.lift(order));
+        let mut product = vec![Rational::zero(); 2 * order];
+        for (i, x) in a.coefficients.iter().enumerate() {
+            for (j, y) in b.coefficients.iter().enumerate() {
+                product[i + j] += x * y;
+            }
+        }
+        Cyclotomic::reduced(order, product)
+    }
+}
+
+impl Zero for Cyclotomic {
+    fn zero() -> Self {
+        Cyclotomic::from_rational(Rational::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(Zero::is_zero)
+    }
+}
+
+impl fmt::Display for Cyclotomic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(k, c)| match k {
+                0 => format!("{}", c),
+                _ => format!("{}ζ{}^{}", c, self.order, k),
+            })
+            .collect::<Vec<_>>();
+
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cyclotomic_polynomial, Cyclotomic, EvalError, RootOfUnity};
+    use crate::polynomial::Polynomial;
+    use num::{complex::Complex64, rational::Rational, Zero};
+
+    fn trefoil() -> Polynomial {
+        "t + t^3 - t^4".parse().unwrap()
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(trefoil().eval(-1isize), Ok(-3));
+        assert_eq!(trefoil().eval(2isize), Ok(-6));
+        assert_eq!(trefoil().eval(1isize), Ok(1));
+
+        let p: Polynomial = "t^-1 + t^-2".parse().unwrap();
+        assert_eq!(p.eval(-1isize), Ok(0));
+        assert_eq!(p.eval(2isize), Err(EvalError::NotRepresentable));
+        assert_eq!(p.eval(0isize), Err(EvalError::DivisionByZero));
+
+        let p: Polynomial = "t^(1/2)".parse().unwrap();
+        assert_eq!(p.eval(4isize), Err(EvalError::NotRepresentable));
+
+        let p: Polynomial = "t^62 + 2t^61".parse().unwrap();
+        assert_eq!(p.eval(2isize), Err(EvalError::Overflow));
+        assert_eq!(p.eval(-2isize), Ok(0));
+        let p: Polynomial = "t^62 - 2t^61".parse().unwrap();
+        assert_eq!(p.eval(-2isize), Err(EvalError::Overflow));
+        let p: Polynomial = "2t^62".parse().unwrap();
+        assert_eq!(p.eval(2isize), Err(EvalError::Overflow));
+    }
+
+    #[test]
+    fn rationals() {
+        assert_eq!(
+            trefoil().eval(Rational::new(1, 2)),
+            Ok(Rational::new(9, 16))
+        );
+
+        let p: Polynomial = "t^-2".parse().unwrap();
+        assert_eq!(p.eval(Rational::new(2, 3)), Ok(Rational::new(9, 4)));
+        assert_eq!(p.eval(Rational::zero()), Err(EvalError::DivisionByZero));
+
+        let p: Polynomial = "t^100".parse().unwrap();
+        assert_eq!(p.eval(Rational::from(2)), Err(EvalError::Overflow));
+        assert_eq!(p.eval(Rational::new(1, 2)), Err(EvalError::Overflow));
+        let p: Polynomial = "t^62 + 2t^61".parse().unwrap();
+        assert_eq!(p.eval(Rational::from(2)), Err(EvalError::Overflow));
+        assert_eq!(p.eval(Rational::from(-2)), Ok(Rational::zero()));
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(trefoil().eval(2.0), Ok(-6.0));
+
+        let p: Polynomial = "t^(1/2) + 1/2".parse().unwrap();
+        assert_eq!(p.eval(4.0), Ok(2.5));
+        assert_eq!(p.eval(-4.0), Err(EvalError::NotRepresentable));
+    }
+
+    #[test]
+    fn complex() {
+        let v = trefoil().eval(Complex64::i()).unwrap();
+        assert!((v - Complex64::new(-1.0, 0.0)).norm() < 1e-12);
+
+        let p: Polynomial = "t^(1/2)".parse().unwrap();
+        let v = p.eval(Complex64::new(-1.0, 0.0)).unwrap();
+        assert!((v - Complex64::i()).norm() < 1e-12);
+    }
+
+    #[test]
+    fn polynomials() {
+        let p: Polynomial = "A^2 + 2A + 3".parse().unwrap();
+        let q: Polynomial = "t - 1".parse().unwrap();
+        assert_eq!(p.eval(&q), Ok("t^2 + 2".parse().unwrap()));
+
+        let p: Polynomial = "2A^(-1/2)".parse().unwrap();
+        let q: Polynomial = "9/4q^6".parse().unwrap();
+        assert_eq!(p.eval(&q), Ok("4/3q^-3".parse().unwrap()));
+
+        let q: Polynomial = "2q^6".parse().unwrap();
+        assert_eq!(p.eval(&q), Err(EvalError::NotRepresentable));
+        let q: Polynomial = "q + 1".parse().unwrap();
+        assert_eq!(p.eval(&q), Err(EvalError::NotRepresentable));
+        assert_eq!(p.eval(&Polynomial::zero()), Err(EvalError::DivisionByZero));
+    }
+
+    #[test]
+    fn roots_of_unity() {
+        // The Jones polynomial of any knot is 1 at a primitive third root of unity.
+        assert_eq!(
+            trefoil().eval(RootOfUnity::primitive(3)),
+            Ok(Cyclotomic::from_rational(Rational::from_integer(1)))
+        );
+        assert_eq!(
+            trefoil()
+                .eval(RootOfUnity::new(2, 1))
+                .unwrap()
+                .to_rational(),
+            Some(Rational::from_integer(-3))
+        );
+
+        let p: Polynomial = "t^(1/2)".parse().unwrap();
+        assert_eq!(
+            p.eval(RootOfUnity::new(2, 1)),
+            Ok(Cyclotomic::root_of_unity(4, 1))
+        );
+
+        let v = trefoil().eval(RootOfUnity::primitive(5)).unwrap();
+        let w = trefoil()
+            .eval(Complex64::from_polar(
+                &1.0,
+                &(2.0 * std::f64::consts::PI / 5.0),
+            ))
+            .unwrap();
+        assert!((v.to_complex() - w).norm() < 1e-12);
+
+        let p: Polynomial = "t^(1/2) - 2t^(-3/2) + 1/3t^(5/3)".parse().unwrap();
+        for order in 1..=12 {
+            for power in 0..order {
+                let v = p.eval(RootOfUnity::new(order, power)).unwrap();
+                let w = p
+                    .eval(Complex64::from_polar(
+                        &1.0,
+                        &(2.0 * std::f64::consts::PI * power as f64 / order as f64),
+                    ))
+                    .unwrap();
+                assert!(
+                    (v.to_complex() - w).norm() < 1e-9,
+                    "e^(2πi·{}/{})",
+                    power,
+                    order
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cyclotomic_arithmetic() {
+        assert_eq!(cyclotomic_polynomial(1), vec![-1, 1]);
+        assert_eq!(cyclotomic_polynomial(2), vec![1, 1]);
+        assert_eq!(cyclotomic_polynomial(3), vec![1, 1, 1]);
+        assert_eq!(cyclotomic_polynomial(4), vec![1, 0, 1]);
+        assert_eq!(cyclotomic_polynomial(6), vec![1, -1, 1]);
+        assert_eq!(cyclotomic_polynomial(12), vec![1, 0, -1, 0, 1]);
+
+        let minus_one = Cyclotomic::from_rational(Rational::from_integer(-1));
+        assert_eq!(Cyclotomic::root_of_unity(4, 2), minus_one);
+        assert_eq!(
+            Cyclotomic::root_of_unity(3, 1) + Cyclotomic::root_of_unity(3, 2),
+            minus_one
+        );
+        assert_eq!(
+            Cyclotomic::root_of_unity(6, 1) * Cyclotomic::root_of_unity(4, 1),
+            Cyclotomic::root_of_unity(12, 5)
+        );
+        assert!((Cyclotomic::root_of_unity(4, 1) + minus_one.clone())
+            .to_rational()
+            .is_none());
+        assert_eq!(format!("{}", Cyclotomic::root_of_unity(5, 2)), "1ζ5^2");
+    }
+}
@@ -0,0 +1,342 @@
+use super::Polynomial;
+use std::{fmt, str::FromStr};
+
+use num::{rational::Rational, One, Signed, Zero};
+
+/// The output formats a `Polynomial` can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The `Display` format, e.g. `-1A^5 + -1A^-3 + 1A^-7`.
+    Plain,
+    /// Human-readable text, e.g. `-A^5 - A^-3 + A^-7`.
+    Pretty,
+    /// LaTeX math mode, e.g. `-A^{5} - A^{-3} + A^{-7}`.
+    Latex,
+    /// Presentation MathML wrapped in a `<math>` element.
+    MathMl,
+    /// Mathematica input, e.g. `-A^5 - A^(-3) + A^(-7)`.
+    Mathematica,
+    /// SageMath (and Python) input, e.g. `-A**5 - A**(-3) + A**(-7)`.
+    Sage,
+}
+
+impl Format {
+    /// The names accepted by `Format::from_str`.
+    pub const NAMES: &'static [&'static str] =
+        &["plain", "pretty", "latex", "mathml", "mathematica", "sage"];
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(Format::Plain),
+            "pretty" => Ok(Format::Pretty),
+            "latex" | "tex" => Ok(Format::Latex),
+            "mathml" => Ok(Format::MathMl),
+            "mathematica" | "wolfram" => Ok(Format::Mathematica),
+            "sage" | "sagemath" => Ok(Format::Sage),
+            _ => Err(format!(
+                "unknown format '{}', expected one of: {}",
+                s,
+                Format::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A `Polynomial` paired with the `Format` to display it in, created by `Polynomial::format`.
+pub struct Formatted<'a> {
+    polynomial: &'a Polynomial,
+    format: Format,
+}
+
+impl Polynomial {
+    /// Returns a value that displays the polynomial in the given format.
+    ///
+    /// ```
+    /// use knot_solver::polynomial::{Format, Polynomial};
+    ///
+    /// let p: Polynomial = "1A^-7 + -1A^-3".parse().unwrap();
+    /// assert_eq!(p.format(Format::Latex).to_string(), "-A^{-3} + A^{-7}");
+    /// ```
+    pub fn format(&self, format: Format) -> Formatted<'_> {
+        Formatted {
+            polynomial: self,
+            format,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Formatted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let style = match self.format {
+            Format::Plain => return write!(f, "{}", self.polynomial),
+            Format::Pretty => &PRETTY,
+            Format::Latex => &LATEX,
+            Format::MathMl => &MATHML,
+            Format::Mathematica => &MATHEMATICA,
+            Format::Sage => &SAGE,
+        };
+
+        write!(f, "{}", style.prefix)?;
+        if self.polynomial.is_zero() {
+            write!(f, "{}", style.zero)?;
+        }
+        for (i, t) in self.polynomial.iter().rev().enumerate() {
+            let sign = match (i, t.coefficient().is_negative()) {
+                (0, false) => "",
+                (0, true) => style.leading_minus,
+                (_, false) => style.plus,
+                (_, true) => style.minus,
+            };
+            let term = (style.term)(
+                t.coefficient().abs(),
+                t.exponent(),
+                self.polynomial.variable,
+            );
+            write!(f, "{}{}", sign, term)?;
+        }
+        write!(f, "{}", style.suffix)
+    }
+}
+
+/// How a format writes the pieces of a polynomial.
+struct Style {
+    prefix: &'static str,
+    suffix: &'static str,
+    zero: &'static str,
+    plus: &'static str,
+    minus: &'static str,
+    leading_minus: &'static str,
+    /// Writes a term given its positive coefficient, its exponent and the variable.
+    term: fn(Rational, Rational, char) -> String,
+}
+
+const PRETTY: Style = Style {
+    prefix: "",
+    suffix: "",
+    zero: "0",
+    plus: " + ",
+    minus: " - ",
+    leading_minus: "-",
+    term: |c, e, x| {
+        let power = if e.is_zero() {
+            return c.to_string();
+        } else if e.is_one() {
+            x.to_string()
+        } else if e.is_integer() {
+            format!("{}^{}", x, e)
+        } else {
+            format!("{}^({})", x, e)
+        };
+
+        if c.is_one() {
+            power
+        } else if c.is_integer() {
+            format!("{}{}", c, power)
+        } else {
+            format!("{} {}", c, power)
+        }
+    },
+};
+
+const LATEX: Style = Style {
+    prefix: "",
+    suffix: "",
+    zero: "0",
+    plus: " + ",
+    minus: " - ",
+    leading_minus: "-",
+    term: |c, e, x| {
+        let coefficient = if c.is_integer() {
+            c.to_string()
+        } else {
+            format!("\\frac{{{}}}{{{}}}", c.numer(), c.denom())
+        };
+        let power = if e.is_zero() {
+            return coefficient;
+        } else if e.is_one() {
+            x.to_string()
+        } else {
+            format!("{}^{{{}}}", x, e)
+        };
+        if c.is_one() {
+            power
+        } else {
+            coefficient + &power
+        }
+    },
+};
+
+const MATHML: Style = Style {
+    prefix: "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow>",
+    suffix: "</mrow></math>",
+    zero: "<mn>0</mn>",
+    plus: "<mo>+</mo>",
+    minus: "<mo>-</mo>",
+    leading_minus: "<mo>-</mo>",
+    term: |c, e, x| {
+        fn number(r: Rational) -> String {
+            let magnitude = if r.is_integer() {
+                format!("<mn>{}</mn>", r.abs())
+            } else {
+                format!(
+                    "<mfrac><mn>{}</mn><mn>{}</mn></mfrac>",
+                    r.numer().abs(),
+                    r.denom()
+                )
+            };
+            if r.is_negative() {
+                format!("<mrow><mo>-</mo>{}</mrow>", magnitude)
+            } else {
+                magnitude
+            }
+        }
+
+        let power = if e.is_zero() {
+            return number(c);
+        } else if e.is_one() {
+            format!("<mi>{}</mi>", x)
+        } else {
+            format!("<msup><mi>{}</mi>{}</msup>", x, number(e))
+        };
+        if c.is_one() {
+            power
+        } else {
+            format!("<mrow>{}<mo>&#x2062;</mo>{}</mrow>", number(c), power)
+        }
+    },
+};
+
+const MATHEMATICA: Style = Style {
+    prefix: "",
+    suffix: "",
+    zero: "0",
+    plus: " + ",
+    minus: " - ",
+    leading_minus: "-",
+    term: |c, e, x| computer_algebra_term(c, e, x, "^"),
+};
+
+const SAGE: Style = Style {
+    prefix: "",
+    suffix: "",
+    zero: "0",
+    plus: " + ",
+    minus: " - ",
+    leading_minus: "-",
+    term: |c, e, x| computer_algebra_term(c, e, x, "**"),
+};
+
+/// Writes a term as computer algebra input using the given power operator.
+fn computer_algebra_term(c: Rational, e: Rational, x: char, pow: &str) -> String {
+    let power = if e.is_zero() {
+        return c.to_string();
+    } else if e.is_one() {
+        x.to_string()
+    } else if e.is_integer() && e.is_positive() {
+        format!("{}{}{}", x, pow, e)
+    } else {
+        format!("{}{}({})", x, pow, e)
+    };
+    if c.is_one() {
+        power
+    } else {
+        format!("{}*{}", c, power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+    use crate::polynomial::Polynomial;
+
+    fn bracket() -> Polynomial {
+        "A^-7 - A^-3 - A^5".parse().unwrap()
+    }
+
+    fn fractional() -> Polynomial {
+        "3/2t^(-1/2) + 2t + 5".parse().unwrap()
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            bracket().format(Format::Plain).to_string(),
+            bracket().to_string()
+        );
+    }
+
+    #[test]
+    fn pretty() {
+        assert_eq!(
+            bracket().format(Format::Pretty).to_string(),
+            "-A^5 - A^-3 + A^-7"
+        );
+        assert_eq!(
+            fractional().format(Format::Pretty).to_string(),
+            "2t + 5 + 3/2 t^(-1/2)"
+        );
+        assert_eq!(Polynomial::zero().format(Format::Pretty).to_string(), "0");
+
+        // Pretty output can be parsed back.
+        for p in &[bracket(), fractional()] {
+            let s = p.format(Format::Pretty).to_string();
+            assert_eq!(&s.parse::<Polynomial>().unwrap(), p);
+        }
+    }
+
+    #[test]
+    fn latex() {
+        assert_eq!(
+            bracket().format(Format::Latex).to_string(),
+            "-A^{5} - A^{-3} + A^{-7}"
+        );
+        assert_eq!(
+            fractional().format(Format::Latex).to_string(),
+            "2t + 5 + \\frac{3}{2}t^{-1/2}"
+        );
+    }
+
+    #[test]
+    fn mathml() {
+        assert_eq!(
+            "2A - A^-3"
+                .parse::<Polynomial>()
+                .unwrap()
+                .format(Format::MathMl)
+                .to_string(),
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mrow>\
+             <mrow><mn>2</mn><mo>&#x2062;</mo><mi>A</mi></mrow>\
+             <mo>-</mo><msup><mi>A</mi><mrow><mo>-</mo><mn>3</mn></mrow></msup>\
+             </mrow></math>"
+        );
+    }
+
+    #[test]
+    fn computer_algebra() {
+        assert_eq!(
+            bracket().format(Format::Mathematica).to_string(),
+            "-A^5 - A^(-3) + A^(-7)"
+        );
+        assert_eq!(
+            fractional().format(Format::Mathematica).to_string(),
+            "2*t + 5 + 3/2*t^(-1/2)"
+        );
+        assert_eq!(
+            bracket().format(Format::Sage).to_string(),
+            "-A**5 - A**(-3) + A**(-7)"
+        );
+    }
+
+    #[test]
+    fn names() {
+        for name in Format::NAMES {
+            assert!(name.parse::<Format>().is_ok());
+        }
+        assert_eq!("LaTeX".parse::<Format>(), Ok(Format::Latex));
+        assert!("html".parse::<Format>().is_err());
+    }
+}
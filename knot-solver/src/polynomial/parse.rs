@@ -0,0 +1,416 @@
+use super::{Polynomial, Term};
+use std::{error::Error, fmt, str::FromStr};
+
+use num::{rational::Rational, Zero};
+
+/// An error produced when a `Polynomial` cannot be parsed, along with the byte offset in the
+/// input where the problem was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolynomialParseError {
+    kind: PolynomialParseErrorKind,
+    position: usize,
+}
+
+impl PolynomialParseError {
+    /// Returns what went wrong.
+    pub fn kind(&self) -> &PolynomialParseErrorKind {
+        &self.kind
+    }
+
+    /// Returns the byte offset in the input at which the error occurred.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// The different ways parsing a `Polynomial` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolynomialParseErrorKind {
+    /// The input contained no terms.
+    Empty,
+    /// A character that does not fit the grammar at this position.
+    UnexpectedCharacter(char),
+    /// The input ended in the middle of a term.
+    UnexpectedEnd,
+    /// A number was too large to be represented.
+    NumberTooLarge,
+    /// A fraction had a denominator of zero.
+    ZeroDenominator,
+    /// A term used a different variable than the terms before it.
+    MixedVariables(char, char),
+}
+
+impl fmt::Display for PolynomialParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.kind {
+            PolynomialParseErrorKind::Empty => write!(f, "empty polynomial"),
+            PolynomialParseErrorKind::UnexpectedCharacter(c) => {
+                write!(f, "unexpected character '{}' at {}", c, self.position)
+            }
+            PolynomialParseErrorKind::UnexpectedEnd => {
+                write!(f, "unexpected end of input at {}", self.position)
+            }
+            PolynomialParseErrorKind::NumberTooLarge => {
+                write!(f, "number too large at {}", self.position)
+            }
+            PolynomialParseErrorKind::ZeroDenominator => {
+                write!(f, "zero denominator at {}", self.position)
+            }
+            PolynomialParseErrorKind::MixedVariables(expected, found) => write!(
+                f,
+                "expected variable '{}' but found '{}' at {}",
+                expected, found, self.position
+            ),
+        }
+    }
+}
+
+impl Error for PolynomialParseError {}
+
+impl FromStr for Polynomial {
+    type Err = PolynomialParseError;
+
+    /// Parses a polynomial such as `3A^4 + 4A^3`, `-A^-3 + A^5` or `t^(1/2) - 2*t`.
+    ///
+    /// Coefficients and exponents may be integers or fractions, coefficients of one may be left
+    /// out, and exponents may be wrapped in parentheses or braces. Every term must use the same
+    /// single-letter variable, which becomes the polynomial's variable.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::new(s).polynomial()
+    }
+}
+
+/// A small recursive descent parser that tracks its byte offset into the input.
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    variable: Option<char>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            input,
+            position: 0,
+            variable: None,
+        }
+    }
+
+    fn polynomial(mut self) -> Result<Polynomial, PolynomialParseError> {
+        let mut terms = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek().is_none() {
+            return Err(self.error(PolynomialParseErrorKind::Empty));
+        }
+
+        let mut negative = self.sign();
+        loop {
+            let term = self.term()?;
+            terms.push(if negative { term * -1 } else { term });
+
+            self.skip_whitespace();
+            negative = match self.peek() {
+                None => break,
+                Some('+') => false,
+                Some('-') => true,
+                Some(c) => return Err(self.error(PolynomialParseErrorKind::UnexpectedCharacter(c))),
+            };
+            self.bump();
+            // Allows the `+ -1A^-3` sequences produced by `Display`.
+            if self.sign() {
+                negative = !negative;
+            }
+        }
+
+        let variable = self.variable.unwrap_or('A');
+        Ok(terms
+            .into_iter()
+            .fold(Polynomial::zero().with_variable(variable), |acc, t| acc + t))
+    }
+
+    /// Parses a single unsigned term, e.g. `3A^4`, `A`, `1/2*t^(1/2)` or `7`.
+    fn term(&mut self) -> Result<Term, PolynomialParseError> {
+        self.skip_whitespace();
+        let coefficient = match self.peek() {
+            Some(c) if c.is_ascii_digit() || c == '(' => {
+                let coefficient = self.rational()?;
+                self.skip_whitespace();
+                if self.peek() == Some('*') {
+                    self.bump();
+                    self.skip_whitespace();
+                    if !self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                        return Err(self.unexpected());
+                    }
+                }
+                Some(coefficient)
+            }
+            _ => None,
+        };
+
+        let exponent = match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                self.variable(c)?;
+                self.bump();
+                self.skip_whitespace();
+                if self.peek() == Some('^') {
+                    self.bump();
+                    self.skip_whitespace();
+                    self.exponent()?
+                } else {
+                    Rational::from_integer(1)
+                }
+            }
+            _ if coefficient.is_some() => Rational::zero(),
+            _ => return Err(self.unexpected()),
+        };
+
+        Ok(Term {
+            coefficient: coefficient.unwrap_or_else(|| Rational::from_integer(1)),
+            exponent,
+        })
+    }
+
+    /// Parses an exponent, which may be bare (`-3`, `1/2`) or grouped (`(1/2)`, `{-7}`).
+    fn exponent(&mut self) -> Result<Rational, PolynomialParseError> {
+        let close = match self.peek() {
+            Some('{') => Some('}'),
+            Some('(') => Some(')'),
+            _ => None,
+        };
+
+        if let Some(close) = close {
+            self.bump();
+            self.skip_whitespace();
+            let negative = self.sign();
+            let exponent = self.fraction()?;
+            self.expect(close)?;
+            Ok(if negative { -exponent } else { exponent })
+        } else {
+            let negative = self.sign();
+            let exponent = self.fraction()?;
+            Ok(if negative { -exponent } else { exponent })
+        }
+    }
+
+    /// Parses an unsigned coefficient, either bare (`3`, `1/2`) or parenthesised (`(-1/2)`).
+    fn rational(&mut self) -> Result<Rational, PolynomialParseError> {
+        if self.peek() == Some('(') {
+            self.bump();
+            self.skip_whitespace();
+            let negative = self.sign();
+            let r = self.fraction()?;
+            self.expect(')')?;
+            Ok(if negative { -r } else { r })
+        } else {
+            self.fraction()
+        }
+    }
+
+    /// Parses an integer optionally followed by `/` and a nonzero integer.
+    fn fraction(&mut self) -> Result<Rational, PolynomialParseError> {
+        let numerator = self.integer()?;
+        self.skip_whitespace();
+        if self.peek() == Some('/') {
+            self.bump();
+            self.skip_whitespace();
+            let start = self.position;
+            let denominator = self.integer()?;
+            if denominator == 0 {
+                return Err(PolynomialParseError {
+                    kind: PolynomialParseErrorKind::ZeroDenominator,
+                    position: start,
+                });
+            }
+            Ok(Rational::new(numerator, denominator))
+        } else {
+            Ok(Rational::from_integer(numerator))
+        }
+    }
+
+    fn integer(&mut self) -> Result<isize, PolynomialParseError> {
+        let start = self.position;
+        let mut value: isize = 0;
+        while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add(c as isize - '0' as isize))
+                .ok_or(PolynomialParseError {
+                    kind: PolynomialParseErrorKind::NumberTooLarge,
+                    position: start,
+                })?;
+            self.bump();
+        }
+
+        if self.position == start {
+            Err(self.unexpected())
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Records the variable of the polynomial, or checks that `c` matches the one already seen.
+    fn variable(&mut self, c: char) -> Result<(), PolynomialParseError> {
+        match self.variable {
+            Some(v) if v != c => Err(self.error(PolynomialParseErrorKind::MixedVariables(v, c))),
+            _ => {
+                self.variable = Some(c);
+                Ok(())
+            }
+        }
+    }
+
+    /// Consumes any number of `+` and `-` signs, returning whether the result is negative.
+    fn sign(&mut self) -> bool {
+        let mut negative = false;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('-') => negative = !negative,
+                Some('+') => {}
+                _ => return negative,
+            }
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PolynomialParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn unexpected(&self) -> PolynomialParseError {
+        match self.peek() {
+            Some(c) => self.error(PolynomialParseErrorKind::UnexpectedCharacter(c)),
+            None => self.error(PolynomialParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn error(&self, kind: PolynomialParseErrorKind) -> PolynomialParseError {
+        PolynomialParseError {
+            kind,
+            position: self.position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polynomial::{Polynomial, PolynomialParseErrorKind, Term};
+    use num::rational::Rational;
+
+    #[test]
+    fn display_format() {
+        assert_eq!(
+            "3A^4 + 4A^3".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![Term::new(3, 4), Term::new(4, 3)])
+        );
+        assert_eq!(
+            "1A^-7 + -1A^-3".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![Term::new(1, -7), Term::new(-1, -3)])
+        );
+
+        let p = Polynomial::from_vec(vec![Term::new(1, -7), Term::new(-1, -3), Term::new(-1, 5)]);
+        assert_eq!(p.to_string().parse::<Polynomial>().unwrap(), p);
+
+        let p = Polynomial::from(Term::new(Rational::new(-3, 2), Rational::new(-1, 4)));
+        assert_eq!(p.to_string().parse::<Polynomial>().unwrap(), p);
+
+        let p = p.with_variable('q');
+        assert_eq!(p.to_string().parse::<Polynomial>().unwrap(), p);
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(
+            "-A^-3 + A^5".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![Term::new(-1, -3), Term::new(1, 5)])
+        );
+        assert_eq!(
+            "t^(1/2) - 2*t + 3".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![
+                Term::new(Rational::new(1, 1), Rational::new(1, 2)),
+                Term::new(-2, 1),
+                Term::new(3, 0),
+            ])
+            .with_variable('t')
+        );
+        assert_eq!(
+            "q^{-2} - q^{-6}".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![Term::new(1, -2), Term::new(-1, -6)]).with_variable('q')
+        );
+        assert_eq!(
+            "(1/2)A^2 - -A".parse::<Polynomial>().unwrap(),
+            Polynomial::from_vec(vec![
+                Term::new(Rational::new(1, 2), Rational::from_integer(2)),
+                Term::new(1, 1),
+            ])
+        );
+        assert_eq!(
+            "A^2 - A^2".parse::<Polynomial>().unwrap(),
+            Polynomial::zero()
+        );
+        assert_eq!("0".parse::<Polynomial>().unwrap(), Polynomial::zero());
+    }
+
+    #[test]
+    fn errors() {
+        let err = "3A^4 + ".parse::<Polynomial>().unwrap_err();
+        assert_eq!(err.kind(), &PolynomialParseErrorKind::UnexpectedEnd);
+        assert_eq!(err.position(), 7);
+
+        let err = "3A^4 + 2t".parse::<Polynomial>().unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &PolynomialParseErrorKind::MixedVariables('A', 't')
+        );
+        assert_eq!(err.position(), 8);
+
+        let err = "3A^4 $ 2".parse::<Polynomial>().unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &PolynomialParseErrorKind::UnexpectedCharacter('$')
+        );
+        assert_eq!(err.position(), 5);
+
+        let err = "A^(1/0)".parse::<Polynomial>().unwrap_err();
+        assert_eq!(err.kind(), &PolynomialParseErrorKind::ZeroDenominator);
+        assert_eq!(err.position(), 5);
+
+        let err = "  ".parse::<Polynomial>().unwrap_err();
+        assert_eq!(err.kind(), &PolynomialParseErrorKind::Empty);
+
+        let err = "A^(1/2".parse::<Polynomial>().unwrap_err();
+        assert_eq!(err.kind(), &PolynomialParseErrorKind::UnexpectedEnd);
+        assert_eq!(err.position(), 6);
+    }
+
+    #[test]
+    fn macro_create() {
+        assert_eq!(
+            polynomial!("-A^-3 + A^5"),
+            Polynomial::from_vec(vec![Term::new(-1, -3), Term::new(1, 5)])
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Random braids and knot diagrams.
+//!
+//! Everything here draws from a generator passed in, so seeding it gives the same results on
+//! every run. `seeded` makes one from a number.
+//!
+//! ```
+//! use knot_solver::random;
+//!
+//! let first = random::braid(&mut random::seeded(7), 4, 10);
+//! let second = random::braid(&mut random::seeded(7), 4, 10);
+//! assert_eq!(first, second);
+//! ```
+
+use crate::{
+    braid::Braid,
+    tangle::{self, NE, NW, SE, SW},
+    Knot,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Creates a random number generator from a seed, which gives the same numbers every time.
+pub fn seeded(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Returns a braid on the given number of strands whose word has the given length, with each
+/// letter chosen uniformly from the generators and their inverses.
+///
+/// # Panics
+///
+/// Panics if the length is not zero and there are fewer than two strands.
+pub fn braid<R: Rng + ?Sized>(rng: &mut R, strands: usize, length: usize) -> Braid {
+    let word = (0..length).map(|_| letter(rng, strands)).collect();
+    Braid::new(strands, word)
+}
+
+/// Starts a random walk in the braid group on the given number of strands.
+///
+/// ```
+/// use knot_solver::random;
+///
+/// let mut walk = random::walk(random::seeded(1), 3);
+/// let first = walk.next().unwrap();
+/// assert_eq!(first.len(), 1);
+/// assert!(walk.take(99).all(|braid| braid.len() <= 100));
+/// ```
+///
+/// # Panics
+///
+/// The walk panics when it takes a step if there are fewer than two strands.
+pub fn walk<R: Rng>(rng: R, strands: usize) -> Walk<R> {
+    Walk {
+        rng,
+        strands,
+        word: Vec::new(),
+    }
+}
+
+/// A random walk in the braid group, which multiplies by a generator or its inverse, chosen
+/// uniformly, at each step. The braids it visits are freely reduced, so a step that undoes the
+/// one before goes back to the braid before that.
+#[derive(Debug, Clone)]
+pub struct Walk<R> {
+    rng: R,
+    strands: usize,
+    word: Vec<isize>,
+}
+
+impl<R: Rng> Iterator for Walk<R> {
+    type Item = Braid;
+
+    fn next(&mut self) -> Option<Braid> {
+        let g = letter(&mut self.rng, self.strands);
+        if self.word.last() == Some(&-g) {
+            self.word.pop();
+        } else {
+            self.word.push(g);
+        }
+        Some(Braid::new(self.strands, self.word.clone()))
+    }
+}
+
+/// Returns a random generator on the given number of strands, or its inverse.
+fn letter<R: Rng + ?Sized>(rng: &mut R, strands: usize) -> isize {
+    assert!(
+        strands >= 2,
+        "there are no generators on fewer than two strands"
+    );
+    let g = rng.gen_range(1, strands as isize);
+    if rng.gen() {
+        g
+    } else {
+        -g
+    }
+}
+
+/// Returns the planar diagram code of a random connected diagram with the given number of
+/// crossings.
+///
+/// The diagram is grown from a figure eight with one crossing. Each new crossing pinches
+/// together two edges on the boundary of a random region, or puts a kink in one edge, and
+/// which strand goes over is chosen at random. The diagrams are not drawn uniformly from all
+/// diagrams with that many crossings, and may be links.
+///
+/// ```
+/// use knot_solver::{random, Knot};
+///
+/// let code = random::planar_diagram_code(&mut random::seeded(3), 8);
+/// assert_eq!(code.len(), 8);
+/// assert_eq!(Knot::from_pd(&code).unwrap().num_regions(), 10);
+/// ```
+pub fn planar_diagram_code<R: Rng + ?Sized>(rng: &mut R, crossings: usize) -> Vec<[usize; 4]> {
+    let (over, other) = grow(rng, crossings);
+    tangle::planar_diagram_code(&over, &other)
+}
+
+/// Returns a random connected diagram with the given number of crossings, made as by
+/// `planar_diagram_code`. The signs of its crossings follow the directions its components were
+/// traced in, even for components that never go under another one.
+pub fn diagram<R: Rng + ?Sized>(rng: &mut R, crossings: usize) -> Knot {
+    let (over, other) = grow(rng, crossings);
+    if over.is_empty() {
+        return Knot::from_pd(&[]).expect("the empty code is a circle");
+    }
+    tangle::diagram(&over, &other)
+}
+
+/// Grows a random diagram, returning for each crossing whether the strand from its northwest
+/// to its southeast goes over the other one, and the crossing and position at the other end of
+/// the arc from each of its positions.
+fn grow<R: Rng + ?Sized>(rng: &mut R, crossings: usize) -> (Vec<bool>, Vec<[(usize, usize); 4]>) {
+    if crossings == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    // The crossing and position at the other end of the arc from each position.
+    let mut other = vec![[(0, SW), (0, NW), (0, NE), (0, SE)]];
+    while other.len() < crossings {
+        let regions = regions(&other);
+        let region = &regions[rng.gen_range(0, regions.len())];
+        let (i, j) = (
+            rng.gen_range(0, region.len()),
+            rng.gen_range(0, region.len()),
+        );
+        // Going clockwise around the region, the edges run from `a` to `b` and from `c` to
+        // `d`, so the new crossing has those ends clockwise around it.
+        let (a, c) = (region[i.min(j)], region[i.max(j)]);
+        let (b, d) = (other[a.0][a.1], other[c.0][c.1]);
+        let x = other.len();
+        let ends = if a == c {
+            [a, b, (x, NE), (x, SE)]
+        } else if a == d {
+            // The region is on both sides of the edge, which can't be pinched with itself.
+            continue;
+        } else {
+            [a, d, c, b]
+        };
+        other.push(ends);
+        for (p, &(e, r)) in ends.iter().enumerate() {
+            other[e][r] = (x, p);
+        }
+    }
+    let over = (0..crossings).map(|_| rng.gen()).collect();
+    (over, other)
+}
+
+/// Returns the positions around the boundary of each region, going clockwise. The edge from
+/// each position runs to the next one.
+fn regions(other: &[[(usize, usize); 4]]) -> Vec<Vec<(usize, usize)>> {
+    let mut seen = vec![[false; 4]; other.len()];
+    let mut regions = Vec::new();
+    for c in 0..other.len() {
+        for p in 0..4 {
+            let mut region = Vec::new();
+            let (mut d, mut q) = (c, p);
+            while !seen[d][q] {
+                seen[d][q] = true;
+                region.push((d, q));
+                let (e, r) = other[d][q];
+                d = e;
+                q = (r + 1) % 4;
+            }
+            if !region.is_empty() {
+                regions.push(region);
+            }
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{braid, diagram, planar_diagram_code, seeded, walk};
+    use crate::{braid::Braid, Knot};
+
+    #[test]
+    fn braids() {
+        let random = braid(&mut seeded(5), 6, 40);
+        assert_eq!((random.strands(), random.len()), (6, 40));
+        assert!(random.word().iter().all(|g| g.unsigned_abs() < 6));
+        assert_eq!(random, braid(&mut seeded(5), 6, 40));
+        assert_ne!(random, braid(&mut seeded(6), 6, 40));
+
+        let steps: Vec<Braid> = walk(seeded(2), 4).take(200).collect();
+        assert_eq!(steps, walk(seeded(2), 4).take(200).collect::<Vec<_>>());
+        let mut previous = Braid::identity(4);
+        for braid in steps {
+            assert_eq!(braid, braid.free_reduce());
+            assert_eq!((braid.len() as isize - previous.len() as isize).abs(), 1);
+            assert_eq!((&previous.inverse() * &braid).free_reduce().len(), 1);
+            previous = braid;
+        }
+    }
+
+    #[test]
+    fn diagrams() {
+        for seed in 0..40 {
+            for crossings in 0..12 {
+                let code = planar_diagram_code(&mut seeded(seed), crossings);
+                assert_eq!(code, planar_diagram_code(&mut seeded(seed), crossings));
+                let knot = Knot::from_pd(&code).unwrap();
+                assert_eq!(knot.num_crossings(), crossings);
+                assert_eq!(knot.num_regions(), crossings + 2);
+            }
+        }
+        // Every component is traced one way through all of its crossings, so the jones
+        // polynomial doesn't change when the diagram is simplified.
+        for seed in 0..300 {
+            let knot = diagram(&mut seeded(seed), 6);
+            let simplified = knot.simplify();
+            assert_eq!(
+                simplified.jones_polynomial(),
+                knot.jones_polynomial(),
+                "seed {}",
+                seed
+            );
+        }
+
+        let knot = diagram(&mut seeded(11), 9);
+        assert_eq!(
+            knot.bracket_polynomial(),
+            Knot::from_pd(&planar_diagram_code(&mut seeded(11), 9))
+                .unwrap()
+                .bracket_polynomial()
+        );
+    }
+}
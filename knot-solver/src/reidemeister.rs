@@ -0,0 +1,338 @@
+//! Simplification of knot diagrams by Reidemeister moves.
+//!
+//! The moves are found and made on the regions around each crossing, where the 0 resolution
+//! joins one pair of opposite regions and the infinity resolution joins the other.
+//!
+//! - A crossing with the same region on two opposite sides splits the diagram in two, and
+//!   turning one half over removes it (Reidemeister I). The regions of the other pair become
+//!   one.
+//! - A region with corners at just two crossings is a bigon, and the regions on its sides are
+//!   the same at both. If one crossing joins the bigon with its 0 resolution and the other with
+//!   its infinity resolution, one strand passes over both and can be pulled away (Reidemeister
+//!   II), which joins the bigon to the regions at its ends.
+//! - A region with corners at three crossings is a triangle. Unless all three crossings join it
+//!   with the same resolution, a strand passes over or under both of the others and can be
+//!   moved across the crossing between them (Reidemeister III). This does not remove any
+//!   crossings, but can make room for the other moves.
+
+use crate::{Crossing, Knot, Orientation, RegionCounter};
+
+/// The number of Reidemeister III moves in a row that `Knot::simplify` makes without finding a
+/// crossing to remove before it gives up.
+const THIRD_MOVES: usize = 100;
+
+impl Knot {
+    /// Returns a diagram of the same knot with crossings removed by Reidemeister moves.
+    ///
+    /// Reidemeister I and II moves are made while there are any. When there are none left,
+    /// Reidemeister III moves chosen at random are made until one of the others becomes
+    /// possible, or until too many have been made. The choices are the same on every run, so
+    /// the result is too.
+    ///
+    /// The writhe changes with the crossings removed, and the bracket polynomial with it, but
+    /// the beta and jones polynomials stay the same.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// // `σ1 σ2 σ1 = σ2 σ1 σ2`, so this closes up to an unlink of three circles.
+    /// let knot = Knot::from_str("abaBAB").unwrap();
+    /// let simplified = knot.simplify();
+    /// assert_eq!(simplified.num_crossings(), 0);
+    /// assert_eq!(simplified.jones_polynomial(), knot.jones_polynomial());
+    /// ```
+    pub fn simplify(&self) -> Knot {
+        let mut knot = self.clone();
+        let mut random = XorShift(0x9E37_79B9_7F4A_7C15);
+        let mut third_moves = 0;
+        loop {
+            if knot.first_move() || knot.second_move() {
+                third_moves = 0;
+                continue;
+            }
+            let moves = knot.third_moves();
+            if moves.is_empty() || third_moves == THIRD_MOVES {
+                return knot;
+            }
+            let (at, crossings) = &moves[random.below(moves.len())];
+            for (&i, c) in at.iter().zip(crossings) {
+                knot.crossings[i] = c.clone();
+            }
+            third_moves += 1;
+        }
+    }
+
+    /// Makes a Reidemeister I move, if there is one.
+    fn first_move(&mut self) -> bool {
+        let found = self.crossings.iter().enumerate().find_map(|(i, c)| {
+            let [p, q] = resolutions(c);
+            match (p.0 == p.1, q.0 == q.1) {
+                (true, false) => Some((i, q)),
+                (false, true) => Some((i, p)),
+                _ => None,
+            }
+        });
+        match found {
+            Some((i, joined)) => {
+                self.remove(&[i], &[joined]);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes a Reidemeister II move, if there is one.
+    fn second_move(&mut self) -> bool {
+        for (bigon, at) in self.corners().into_iter().enumerate() {
+            if let [c, d] = at[..] {
+                if c == d {
+                    continue;
+                }
+                let (first, second) = (
+                    Corner::new(&self.crossings[c], bigon),
+                    Corner::new(&self.crossings[d], bigon),
+                );
+                if first.resolution != second.resolution && first.sides == second.sides {
+                    self.remove(
+                        &[c, d],
+                        &[(bigon, first.opposite), (bigon, second.opposite)],
+                    );
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the Reidemeister III moves that can be made, as the crossings around a triangle
+    /// and the crossings that replace them.
+    fn third_moves(&self) -> Vec<([usize; 3], Vec<Crossing>)> {
+        let mut moves = Vec::new();
+        for (triangle, at) in self.corners().into_iter().enumerate() {
+            let at = match at[..] {
+                [c, d, e] if c != d && d != e && c != e => [c, d, e],
+                _ => continue,
+            };
+            let corners: Vec<Corner> = at
+                .iter()
+                .map(|&c| Corner::new(&self.crossings[c], triangle))
+                .collect();
+            if corners
+                .iter()
+                .all(|c| c.resolution == corners[0].resolution)
+                || corners
+                    .iter()
+                    .any(|c| c.sides.0 == triangle || c.sides.1 == triangle)
+            {
+                continue;
+            }
+            // The region on the far side of the edge of the triangle opposite each crossing,
+            // which is on a side of both of the other crossings.
+            let far: Option<Vec<usize>> = (0..3)
+                .map(|i| {
+                    let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                    shared(corners[j].sides, corners[k].sides)
+                        .filter(|&r| r != corners[i].sides.0 && r != corners[i].sides.1)
+                })
+                .collect();
+            let far = match far {
+                Some(far) => far,
+                None => continue,
+            };
+            // Moving the strand across turns the triangle over: the regions beyond the edges
+            // and beyond the corners of the old triangle swap places.
+            let crossings = (0..3)
+                .map(|i| {
+                    let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                    let c = &self.crossings[at[i]];
+                    let mut pairs = [(triangle, far[i]); 2];
+                    pairs[1 - corners[i].resolution] = (corners[j].opposite, corners[k].opposite);
+                    Crossing::with_resolutions(c.orientation, pairs)
+                })
+                .collect();
+            moves.push((at, crossings));
+        }
+        moves
+    }
+
+    /// Returns the crossings at the corners of each region, with a crossing listed twice if two
+    /// of its corners are in the region.
+    fn corners(&self) -> Vec<Vec<usize>> {
+        let mut corners = vec![Vec::new(); self.num_regions()];
+        for (i, c) in self.crossings.iter().enumerate() {
+            for &r in &[c.top, c.bottom, c.left, c.right] {
+                corners[r].push(i);
+            }
+        }
+        corners
+    }
+
+    /// Removes the given crossings and joins the given pairs of regions, numbering the regions
+    /// that are left in the same order as before.
+    fn remove(&mut self, removed: &[usize], joined: &[(usize, usize)]) {
+        let mut regions = RegionCounter::new(self.num_regions());
+        for &(a, b) in joined {
+            regions.combine(a, b);
+        }
+        let mut number = vec![None; self.num_regions()];
+        let mut next = 0;
+        let renumber: Vec<usize> = (0..self.num_regions())
+            .map(|r| {
+                *number[regions.find(r)].get_or_insert_with(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+
+        let crossings = std::mem::take(&mut self.crossings);
+        self.crossings = crossings
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !removed.contains(i))
+            .map(|(_, c)| Crossing {
+                top: renumber[c.top],
+                bottom: renumber[c.bottom],
+                left: renumber[c.left],
+                right: renumber[c.right],
+                orientation: c.orientation,
+            })
+            .collect();
+        self.region_num = regions.current_count();
+    }
+}
+
+impl Crossing {
+    /// Creates a crossing from the regions joined by its 0 and infinity resolutions.
+    fn with_resolutions(orientation: Orientation, pairs: [(usize, usize); 2]) -> Crossing {
+        let [(a, b), (c, d)] = pairs;
+        match orientation {
+            Orientation::Positive => Crossing {
+                top: a,
+                bottom: b,
+                left: c,
+                right: d,
+                orientation,
+            },
+            Orientation::Negative => Crossing {
+                top: c,
+                bottom: d,
+                left: a,
+                right: b,
+                orientation,
+            },
+        }
+    }
+}
+
+/// Returns the regions joined by the 0 and by the infinity resolution of a crossing.
+fn resolutions(c: &Crossing) -> [(usize, usize); 2] {
+    [c.smoothing(false), c.smoothing(true)]
+}
+
+/// How a region meets a crossing at one of its corners.
+struct Corner {
+    /// `0` if the 0 resolution joins the region to the one opposite it, or `1` if the infinity
+    /// resolution does.
+    resolution: usize,
+    /// The region opposite.
+    opposite: usize,
+    /// The regions on either side, in order.
+    sides: (usize, usize),
+}
+
+impl Corner {
+    fn new(c: &Crossing, region: usize) -> Self {
+        let pairs = resolutions(c);
+        let resolution = if pairs[0].0 == region || pairs[0].1 == region {
+            0
+        } else {
+            1
+        };
+        let (a, b) = pairs[resolution];
+        let (c, d) = pairs[1 - resolution];
+        Corner {
+            resolution,
+            opposite: if a == region { b } else { a },
+            sides: (c.min(d), c.max(d)),
+        }
+    }
+}
+
+/// Returns the one region in both pairs, if there is exactly one.
+fn shared(a: (usize, usize), b: (usize, usize)) -> Option<usize> {
+    let in_b = |r: usize| r == b.0 || r == b.1;
+    match (in_b(a.0), in_b(a.1)) {
+        (true, false) => Some(a.0),
+        (false, true) => Some(a.1),
+        _ => None,
+    }
+}
+
+/// A xorshift generator for choosing moves, always started from the same seed.
+struct XorShift(u64);
+
+impl XorShift {
+    /// Returns a number less than `n`.
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Knot;
+    use std::str::FromStr;
+
+    fn simplified(knot: &Knot) -> Knot {
+        let simplified = knot.simplify();
+        assert!(simplified.num_crossings() <= knot.num_crossings());
+        assert_eq!(simplified.beta_polynomial(), knot.beta_polynomial());
+        simplified
+    }
+
+    #[test]
+    fn moves() {
+        // Kinks, from the first move.
+        let kink = simplified(&Knot::from_str("a").unwrap());
+        assert_eq!((kink.num_crossings(), kink.num_regions()), (0, 2));
+        assert_eq!(
+            simplified(&Knot::from_str("aaab").unwrap()).num_crossings(),
+            3
+        );
+        // Two separate kinks leave two separate circles.
+        assert_eq!(
+            simplified(&Knot::from_pd(&[[1, 1, 2, 2], [3, 3, 4, 4]]).unwrap()).num_regions(),
+            3
+        );
+
+        // Clasps, from the second move. Both leave a circle for each strand.
+        let unlink = simplified(&Knot::from_str("aA").unwrap());
+        assert_eq!((unlink.num_crossings(), unlink.num_regions()), (0, 3));
+        let unlink = simplified(&Knot::from_str("aBbA").unwrap());
+        assert_eq!((unlink.num_crossings(), unlink.num_regions()), (0, 4));
+        assert_eq!(
+            simplified(&Knot::from_str("aa").unwrap()).num_crossings(),
+            2
+        );
+
+        // The third move is needed to find the second ones.
+        for word in &["abaBAB", "BAbabaBABabA", "abcBAbCB"] {
+            let unlink = simplified(&Knot::from_str(word).unwrap());
+            assert_eq!(unlink.num_crossings(), 0, "{}", word);
+        }
+
+        // Reduced alternating diagrams have nothing to remove.
+        let figure_eight =
+            Knot::from_pd(&[[4, 2, 5, 1], [8, 6, 1, 5], [6, 3, 7, 4], [2, 7, 3, 8]]).unwrap();
+        assert_eq!(simplified(&figure_eight).num_crossings(), 4);
+        assert_eq!(
+            simplified(&Knot::from_str("aBaB").unwrap()).num_crossings(),
+            4
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Satellites of knots given as braid closures.
+//!
+//! A satellite follows a companion knot around, so its diagram is made by replacing each strand
+//! of the companion with several parallel strands. These run alongside the companion in the
+//! plane of the diagram, and so twist around each other once for each unit of its writhe.
+//! Twists are added or taken away to make up for this.
+
+use crate::{
+    braid::Braid,
+    tangle::{Tangle, NE, NW, SE, SW},
+    Knot,
+};
+
+/// The sign of the crossings in the clasp of a Whitehead double.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clasp {
+    /// Both crossings are positive, however the double is oriented.
+    Positive,
+    /// Both crossings are negative.
+    Negative,
+}
+
+impl Braid {
+    /// Returns the braid whose closure is the `(p, q)` cable of the closure of this braid,
+    /// which winds `p` times along the companion and `q` times around it.
+    ///
+    /// Every strand is replaced by `p` parallel strands, and every crossing by the `p^2`
+    /// crossings between them, all of the same sign. The closure must be a knot, since its
+    /// writhe is taken as the twisting of the parallel strands.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the closure of the braid has more than one component.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// let kink: Braid = "a".parse().unwrap();
+    /// assert_eq!(kink.cable(2, 3).to_string(), "bcaba");
+    /// ```
+    pub fn cable(&self, p: usize, q: i32) -> Braid {
+        let components = components(self);
+        assert!(
+            components == 1,
+            "the companion must be a knot, but the closure of {} on {} strands has {} components",
+            self,
+            self.strands(),
+            components
+        );
+        let mut word = Vec::with_capacity(self.len() * p * p);
+        for &g in self.word() {
+            // The last strand of the left block passes the right block first, then the one
+            // before it, and so on.
+            let before = (g.unsigned_abs() - 1) * p;
+            for k in 0..p {
+                for j in 0..p {
+                    word.push(g.signum() * (before + p - k + j) as isize);
+                }
+            }
+        }
+        let pattern = Braid::torus(p, q - p as i32 * self.exponent_sum() as i32);
+        word.extend_from_slice(pattern.word());
+        Braid::new(self.strands() * p, word)
+    }
+}
+
+impl Knot {
+    /// Creates the `(p, q)` cable of the closure of a braid, which must be a knot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the closure of the companion has more than one component.
+    ///
+    /// ```
+    /// use knot_solver::{braid::Braid, Knot};
+    ///
+    /// // Cables of the unknot are torus knots.
+    /// assert_eq!(
+    ///     Knot::cable(&Braid::identity(1), 2, 5).jones_polynomial(),
+    ///     Knot::torus(2, 5).jones_polynomial()
+    /// );
+    /// ```
+    pub fn cable(companion: &Braid, p: usize, q: i32) -> Knot {
+        companion.cable(p, q).closure()
+    }
+
+    /// Creates the Whitehead double of the closure of a braid, which must be a knot.
+    ///
+    /// The double is made of two strands that follow the companion with `twists` full twists
+    /// between them, which is the linking number of the companion with either strand, and are
+    /// joined by a clasp of two crossings with the given sign.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the closure of the companion has more than one component.
+    ///
+    /// ```
+    /// use knot_solver::{braid::Braid, Clasp, Knot};
+    ///
+    /// let double = Knot::whitehead_double(&Braid::identity(1), 0, Clasp::Positive);
+    /// assert_eq!(double.writhe(), 2);
+    /// ```
+    pub fn whitehead_double(companion: &Braid, twists: isize, clasp: Clasp) -> Knot {
+        let doubled = companion.cable(2, 2 * twists as i32);
+        let n = doubled.strands();
+        // The strands of the clasp run in opposite directions, so crossings that would be
+        // positive for parallel strands are negative.
+        let clasp = Tangle::zero().twist_eastern(match clasp {
+            Clasp::Positive => -2,
+            Clasp::Negative => 2,
+        });
+        // The clasp sits on top of the first two strands, and its northern ends go around the
+        // outside to the bottom of those strands.
+        let clasped = 2 * n;
+        let pairs: Vec<(usize, usize)> = vec![
+            (clasped + SW, 0),
+            (clasped + SE, 1),
+            (clasped + NW, n),
+            (clasped + NE, n + 1),
+        ]
+        .into_iter()
+        .chain((2..n).map(|i| (i, n + i)))
+        .collect();
+        Tangle::braid(&doubled).beside(clasp, clasped).close(&pairs)
+    }
+}
+
+/// Returns the number of components of the closure of a braid, which is the number of cycles of
+/// the permutation it makes of its strands.
+fn components(braid: &Braid) -> usize {
+    let mut position: Vec<usize> = (0..braid.strands()).collect();
+    for &g in braid.word() {
+        position.swap(g.unsigned_abs() - 1, g.unsigned_abs());
+    }
+    let mut seen = vec![false; braid.strands()];
+    let mut count = 0;
+    for start in 0..braid.strands() {
+        if seen[start] {
+            continue;
+        }
+        count += 1;
+        let mut strand = start;
+        while !seen[strand] {
+            seen[strand] = true;
+            strand = position[strand];
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{braid::Braid, BracketMethod, Clasp, Knot};
+    use std::str::FromStr;
+
+    fn jones(knot: &Knot) -> crate::polynomial::Polynomial {
+        knot.jones_polynomial_with(BracketMethod::Decomposition)
+    }
+
+    #[test]
+    fn cables() {
+        let unknots = [
+            Braid::identity(1),
+            "a".parse().unwrap(),
+            "aB".parse().unwrap(),
+        ];
+        for unknot in &unknots {
+            for &(p, q) in &[(2, 3), (2, -5), (3, 2), (1, 4)] {
+                assert_eq!(
+                    jones(&Knot::cable(unknot, p, q)),
+                    jones(&Knot::torus(p, q)),
+                    "({}, {}) cable of {}",
+                    p,
+                    q,
+                    unknot
+                );
+            }
+        }
+
+        let trefoil: Braid = "aaa".parse().unwrap();
+        assert_eq!(
+            jones(&Knot::cable(&trefoil, 1, 0)),
+            jones(&trefoil.closure())
+        );
+        let cable = jones(&Knot::cable(&trefoil, 2, 1));
+        assert_eq!(cable, jones(&Knot::cable(&"abab".parse().unwrap(), 2, 1)));
+        assert_ne!(cable, jones(&Knot::cable(&trefoil, 2, -1)));
+    }
+
+    #[test]
+    fn components() {
+        for &(braid, components) in &[
+            ("", 1),
+            ("a", 1),
+            ("aa", 2),
+            ("ab", 1),
+            ("aBaB", 1),
+            ("ac", 2),
+        ] {
+            let braid: Braid = braid.parse().unwrap();
+            assert_eq!(super::components(&braid), components, "{}", braid);
+        }
+        assert_eq!(super::components(&Braid::new(3, vec![1])), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "the companion must be a knot")]
+    fn link_cable() {
+        Knot::cable(&"aa".parse().unwrap(), 2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "the companion must be a knot")]
+    fn link_double() {
+        Knot::whitehead_double(&"aabb".parse().unwrap(), 0, Clasp::Positive);
+    }
+
+    #[test]
+    fn whitehead_doubles() {
+        let unknot = Knot::from_pd(&[]).unwrap().jones_polynomial();
+        for companion in &[
+            Braid::identity(1),
+            "a".parse().unwrap(),
+            "aB".parse().unwrap(),
+        ] {
+            for &clasp in &[Clasp::Positive, Clasp::Negative] {
+                assert_eq!(jones(&Knot::whitehead_double(companion, 0, clasp)), unknot);
+            }
+            // Doubles of the unknot are twist knots.
+            assert_eq!(
+                jones(&Knot::whitehead_double(companion, -1, Clasp::Positive)),
+                Knot::from_str("aaa").unwrap().jones_polynomial()
+            );
+            assert_eq!(
+                jones(&Knot::whitehead_double(companion, 1, Clasp::Positive)),
+                Knot::from_str("aBaB").unwrap().jones_polynomial()
+            );
+            assert_eq!(
+                jones(&Knot::whitehead_double(companion, 1, Clasp::Negative)),
+                Knot::from_str("AAA").unwrap().jones_polynomial()
+            );
+        }
+
+        let trefoil: Braid = "aaa".parse().unwrap();
+        let double = Knot::whitehead_double(&trefoil, 0, Clasp::Positive);
+        assert_eq!(double.num_crossings(), 20);
+        assert_ne!(jones(&double), unknot);
+        assert_eq!(
+            jones(&double),
+            jones(&Knot::whitehead_double(
+                &"abab".parse().unwrap(),
+                0,
+                Clasp::Positive
+            ))
+        );
+    }
+}
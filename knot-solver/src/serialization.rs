@@ -0,0 +1,278 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Polynomials are written as their variable and a list of `[exponent, coefficient]` pairs, with
+//! each rational number written as an integer or a `[numerator, denominator]` pair. Knots are
+//! written as their diagram: the number of regions and, for each crossing, the regions around
+//! it and its orientation.
+
+use crate::{
+    polynomial::{Polynomial, Term},
+    Crossing, Knot, RegionCounter, StateHistogram,
+};
+use std::{convert::TryFrom, fmt};
+
+use num::rational::Rational;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A rational number as it appears in serialized data.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RationalData {
+    Integer(isize),
+    Fraction(isize, isize),
+}
+
+impl From<Rational> for RationalData {
+    fn from(r: Rational) -> Self {
+        if r.is_integer() {
+            RationalData::Integer(r.to_integer())
+        } else {
+            RationalData::Fraction(*r.numer(), *r.denom())
+        }
+    }
+}
+
+impl TryFrom<RationalData> for Rational {
+    type Error = &'static str;
+
+    fn try_from(r: RationalData) -> Result<Self, Self::Error> {
+        match r {
+            RationalData::Integer(n) => Ok(Rational::from_integer(n)),
+            RationalData::Fraction(_, 0) => Err("fraction with a zero denominator"),
+            RationalData::Fraction(n, d) => Ok(Rational::new(n, d)),
+        }
+    }
+}
+
+impl Serialize for Term {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (
+            RationalData::from(self.exponent()),
+            RationalData::from(self.coefficient()),
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Term {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (exponent, coefficient) = <(RationalData, RationalData)>::deserialize(deserializer)?;
+        Ok(Term::new(
+            Rational::try_from(coefficient).map_err(D::Error::custom)?,
+            Rational::try_from(exponent).map_err(D::Error::custom)?,
+        ))
+    }
+}
+
+#[derive(Serialize)]
+struct PolynomialRef<'a> {
+    variable: char,
+    terms: Vec<&'a Term>,
+}
+
+#[derive(Deserialize)]
+struct PolynomialData {
+    variable: char,
+    terms: Vec<Term>,
+}
+
+impl Serialize for Polynomial {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PolynomialRef {
+            variable: self.variable(),
+            terms: self.iter().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Polynomial {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = PolynomialData::deserialize(deserializer)?;
+        // Summing the terms merges any that share an exponent.
+        Ok(data
+            .terms
+            .into_iter()
+            .fold(Polynomial::zero(), |p, t| p + t)
+            .with_variable(data.variable))
+    }
+}
+
+/// The serialized form of a `Knot`, checked before it is turned into one.
+#[derive(Deserialize)]
+pub(crate) struct KnotData {
+    regions: usize,
+    crossings: Vec<Crossing>,
+}
+
+/// The reasons a deserialized knot can be invalid.
+#[derive(Debug)]
+pub(crate) enum InvalidKnot {
+    /// A crossing refers to a region that does not exist.
+    Region { crossing: usize, region: usize },
+    /// The regions do not fit together in the plane.
+    NotPlanar,
+}
+
+impl fmt::Display for InvalidKnot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            InvalidKnot::Region { crossing, region } => write!(
+                f,
+                "crossing {} refers to region {}, which does not exist",
+                crossing, region
+            ),
+            InvalidKnot::NotPlanar => write!(f, "the diagram is not planar"),
+        }
+    }
+}
+
+impl TryFrom<KnotData> for Knot {
+    type Error = InvalidKnot;
+
+    fn try_from(data: KnotData) -> Result<Self, Self::Error> {
+        for (i, c) in data.crossings.iter().enumerate() {
+            if let Some(&region) = [c.top, c.bottom, c.left, c.right]
+                .iter()
+                .find(|&&r| r >= data.regions)
+            {
+                return Err(InvalidKnot::Region {
+                    crossing: i,
+                    region,
+                });
+            }
+        }
+
+        // Joining the regions on opposite sides of every crossing leaves two sets for each
+        // piece of the diagram, the regions of either colour of a checkerboard colouring, except
+        // that separate pieces share their outer region. Each circle with no crossings adds a
+        // region of its own. A planar piece with `n` crossings has `n + 2` regions, so the
+        // regions must number the crossings and the sets together. A diagram has at least one
+        // piece or circle, so there are always at least two sets.
+        let mut sides = RegionCounter::new(data.regions);
+        for c in &data.crossings {
+            sides.combine(c.top, c.bottom);
+            sides.combine(c.left, c.right);
+        }
+        if sides.current_count() < 2 || data.regions != data.crossings.len() + sides.current_count()
+        {
+            return Err(InvalidKnot::NotPlanar);
+        }
+
+        Ok(Knot {
+            crossings: data.crossings,
+            region_num: data.regions,
+        })
+    }
+}
+
+/// The serialized form of a `StateHistogram`, checked before it is turned into one.
+#[derive(Deserialize)]
+pub(crate) struct HistogramData {
+    crossings: usize,
+    counts: Vec<Vec<u64>>,
+}
+
+impl TryFrom<HistogramData> for StateHistogram {
+    type Error = String;
+
+    fn try_from(data: HistogramData) -> Result<Self, Self::Error> {
+        if let Some(circles) = data
+            .counts
+            .iter()
+            .position(|row| !row.is_empty() && row.len() != data.crossings + 1)
+        {
+            return Err(format!(
+                "the counts for {} circles do not match {} crossings",
+                circles, data.crossings
+            ));
+        }
+
+        Ok(StateHistogram {
+            crossings: data.crossings,
+            counts: data.counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        polynomial::{Polynomial, Term},
+        Knot,
+    };
+    use num::rational::Rational;
+    use std::str::FromStr;
+
+    #[test]
+    fn polynomial() {
+        let p: Polynomial = "A^-7 - A^-3 - A^5".parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+        assert_eq!(json, r#"{"variable":"A","terms":[[-7,1],[-3,-1],[5,-1]]}"#);
+        assert_eq!(serde_json::from_str::<Polynomial>(&json).unwrap(), p);
+
+        let p: Polynomial = "3/2t^(-1/4) + t".parse().unwrap();
+        let json = serde_json::to_string(&p).unwrap();
+        assert_eq!(json, r#"{"variable":"t","terms":[[[-1,4],[3,2]],[1,1]]}"#);
+        assert_eq!(serde_json::from_str::<Polynomial>(&json).unwrap(), p);
+
+        // Terms sharing an exponent are merged.
+        let p: Polynomial =
+            serde_json::from_str(r#"{"variable":"q","terms":[[2,1],[2,3]]}"#).unwrap();
+        assert_eq!(p, Polynomial::from(Term::new(4, 2)).with_variable('q'));
+
+        assert!(
+            serde_json::from_str::<Polynomial>(r#"{"variable":"A","terms":[[[1,0],1]]}"#).is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<Term>("[[1,2],-3]").unwrap(),
+            Term::new(Rational::from_integer(-3), Rational::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn knot() {
+        let knot = Knot::from_str("aaA").unwrap();
+        let json = serde_json::to_string(&knot).unwrap();
+        assert_eq!(
+            json,
+            r#"{"crossings":[{"top":4,"bottom":2,"left":0,"right":1,"orientation":"positive"},{"top":2,"bottom":3,"left":0,"right":1,"orientation":"positive"},{"top":3,"bottom":4,"left":0,"right":1,"orientation":"negative"}],"regions":5}"#
+        );
+
+        let copy: Knot = serde_json::from_str(&json).unwrap();
+        assert_eq!(copy.num_regions(), knot.num_regions());
+        assert_eq!(copy.bracket_polynomial(), knot.bracket_polynomial());
+
+        let err = serde_json::from_str::<Knot>(
+            r#"{"crossings":[{"top":0,"bottom":0,"left":0,"right":2,"orientation":"positive"}],"regions":2}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("crossing 0 refers to region 2"));
+
+        // One crossing needs three regions.
+        let err = serde_json::from_str::<Knot>(
+            r#"{"crossings":[{"top":0,"bottom":0,"left":1,"right":1,"orientation":"positive"}],"regions":2}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("the diagram is not planar"));
+        for regions in 0..2 {
+            let err = serde_json::from_str::<Knot>(&format!(
+                r#"{{"crossings":[],"regions":{}}}"#,
+                regions
+            ))
+            .unwrap_err();
+            assert!(err.to_string().contains("the diagram is not planar"));
+        }
+
+        let trefoil = Knot::from_str("aaa").unwrap();
+        for knot in &[
+            Knot::from_pd(&[]).unwrap(),
+            trefoil.disjoint_union(&trefoil),
+            trefoil.connected_sum(&Knot::from_str("aBaB").unwrap()),
+            Knot::from_pd(&[]).unwrap().disjoint_union(&trefoil),
+        ] {
+            let copy: Knot = serde_json::from_str(&serde_json::to_string(knot).unwrap()).unwrap();
+            assert_eq!(copy.bracket_polynomial(), knot.bracket_polynomial());
+        }
+    }
+}
@@ -0,0 +1,541 @@
+#[cfg(feature = "serde")]
+use crate::serialization;
+use crate::{
+    polynomial::{Polynomial, Term},
+    Knot,
+};
+use std::{collections::VecDeque, ops::Range};
+
+/// The orders in which the `2^n` states of a knot diagram can be visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enumeration {
+    /// Builds the region connectivity of every state from scratch, in binary order.
+    Direct,
+    /// Visits the states in Gray-code order, so that consecutive states differ by a single
+    /// crossing, and updates the circle count incrementally.
+    GrayCode,
+}
+
+/// The number of states of a diagram with each combination of circle count and smoothing
+/// difference, as produced by `Knot::state_histogram`.
+///
+/// The table has room for at most `n + 1` distinct differences for a diagram with `n`
+/// crossings, so its size depends only on the diagram and not on the number of states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serialization::HistogramData")
+)]
+pub struct StateHistogram {
+    pub(crate) crossings: usize,
+    /// The counts indexed by circle count, then by `(diff + crossings) / 2`.
+    pub(crate) counts: Vec<Vec<u64>>,
+}
+
+impl StateHistogram {
+    /// Creates an empty histogram for a diagram with the given number of crossings.
+    pub fn new(crossings: usize) -> Self {
+        StateHistogram {
+            crossings,
+            counts: Vec::new(),
+        }
+    }
+
+    /// Counts one state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `diff` is not a possible smoothing difference for the number of crossings.
+    pub fn add(&mut self, circles: usize, diff: i16) {
+        let column = self.column(diff);
+        if circles >= self.counts.len() {
+            self.counts.resize(circles + 1, Vec::new());
+        }
+        let row = &mut self.counts[circles];
+        if row.is_empty() {
+            row.resize(self.crossings + 1, 0);
+        }
+        row[column] += 1;
+    }
+
+    /// Combines the counts of two histograms for the same diagram.
+    pub fn merge(mut self, mut other: StateHistogram) -> StateHistogram {
+        assert_eq!(self.crossings, other.crossings);
+        if other.counts.len() > self.counts.len() {
+            std::mem::swap(&mut self, &mut other);
+        }
+        for (row, other) in self.counts.iter_mut().zip(other.counts) {
+            if row.is_empty() {
+                *row = other;
+            } else {
+                row.iter_mut().zip(other).for_each(|(a, b)| *a += b);
+            }
+        }
+        self
+    }
+
+    /// Returns the number of states with the given circle count and smoothing difference, which
+    /// is zero for a difference that no state of the diagram can have.
+    pub fn get(&self, circles: usize, diff: i16) -> u64 {
+        self.position(diff)
+            .and_then(|column| self.counts.get(circles)?.get(column))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Iterates over the circle count, smoothing difference and number of states of every
+    /// combination that occurs, ordered by circle count and then by difference.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, i16, u64)> + '_ {
+        let crossings = self.crossings as i16;
+        self.counts
+            .iter()
+            .enumerate()
+            .flat_map(move |(circles, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, &count)| count > 0)
+                    .map(move |(i, &count)| (circles, 2 * i as i16 - crossings, count))
+            })
+    }
+
+    /// Assembles the bracket polynomial from the counted states.
+    ///
+    /// Every state with `c` circles and smoothing difference `d` contributes
+    /// `A^d (-A^2 - A^-2)^(c - 1)`. The states are grouped by circle count, so that each power of
+    /// `-A^2 - A^-2` is formed once, by multiplying the previous power, and then multiplied by the
+    /// sum of the `A^d` of every state with that many circles.
+    pub fn bracket_polynomial(&self) -> Polynomial {
+        let loop_value = Polynomial::from_vec(vec![Term::new(-1, 2), Term::new(-1, -2)]);
+        let mut power = Polynomial::from(Term::one());
+        let mut bracket = Polynomial::zero();
+        for (circles, row) in self.counts.iter().enumerate().skip(1) {
+            if row.iter().any(|&count| count > 0) {
+                let diffs = row
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &count)| count > 0)
+                    .map(|(i, &count)| {
+                        Term::new(count as isize, 2 * i as isize - self.crossings as isize)
+                    })
+                    .collect();
+                bracket += Polynomial::from_vec(diffs) * power.clone();
+            }
+            if circles + 1 < self.counts.len() {
+                power = power * loop_value.clone();
+            }
+        }
+        bracket
+    }
+
+    /// Returns the number of crossings of the diagram the states belong to.
+    pub fn crossings(&self) -> usize {
+        self.crossings
+    }
+
+    /// Returns the total number of states counted.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().flatten().sum()
+    }
+
+    fn column(&self, diff: i16) -> usize {
+        self.position(diff).unwrap_or_else(|| {
+            panic!(
+                "invalid smoothing difference {} for {} crossings",
+                diff, self.crossings
+            )
+        })
+    }
+
+    /// Returns the column of a smoothing difference, or `None` if no state can have it.
+    fn position(&self, diff: i16) -> Option<usize> {
+        let shifted = diff as isize + self.crossings as isize;
+        if shifted >= 0 && shifted % 2 == 0 && shifted as usize <= 2 * self.crossings {
+            Some(shifted as usize / 2)
+        } else {
+            None
+        }
+    }
+}
+
+/// Walks a range of states in Gray-code order while keeping track of which regions are
+/// connected.
+///
+/// Every crossing contributes one edge to a graph on the regions, joining the two regions its
+/// smoothing opens up, and the number of circles in a state is one less than the number of
+/// components of that graph. Re-smoothing one crossing of a planar diagram always changes the
+/// number of circles by exactly one, so a flip either merges two components, which relabels the
+/// smaller one, or splits one, which is found by searching outwards from both ends of the removed
+/// edge until one search runs out. Either way the cost of a flip is proportional to the smaller
+/// of the components involved rather than to the size of the diagram.
+pub(crate) struct GrayWalker<'a> {
+    knot: &'a Knot,
+    /// The crossings touching each region, once for every corner of the crossing in the region.
+    incidence: Vec<Vec<usize>>,
+    /// The current smoothing of every crossing, as in the bits of a state index.
+    state: Vec<bool>,
+    /// The component every region belongs to.
+    label: Vec<usize>,
+    /// The number of regions in every component, indexed by label.
+    size: Vec<usize>,
+    free_labels: Vec<usize>,
+    components: usize,
+    diff: i16,
+    /// Search bookkeeping, reused between flips.
+    mark: Vec<u32>,
+    epoch: u32,
+    queues: [VecDeque<usize>; 2],
+    visited: [Vec<usize>; 2],
+}
+
+impl<'a> GrayWalker<'a> {
+    pub(crate) fn new(knot: &'a Knot) -> Self {
+        let regions = knot.num_regions();
+        let mut incidence = vec![Vec::new(); regions];
+        for (i, c) in knot.crossings.iter().enumerate() {
+            for &r in &[c.top, c.bottom, c.left, c.right] {
+                incidence[r].push(i);
+            }
+        }
+
+        GrayWalker {
+            knot,
+            incidence,
+            state: vec![false; knot.num_crossings()],
+            label: vec![0; regions],
+            size: Vec::with_capacity(regions),
+            free_labels: Vec::new(),
+            components: 0,
+            diff: 0,
+            mark: vec![0; regions],
+            epoch: 0,
+            queues: [VecDeque::new(), VecDeque::new()],
+            visited: [Vec::new(), Vec::new()],
+        }
+    }
+
+    /// Returns an iterator over the number of circles and the smoothing difference of every state
+    /// whose Gray-code position lies in `range`.
+    pub(crate) fn states(self, range: Range<u128>) -> GrayStates<'a> {
+        GrayStates {
+            walker: self,
+            position: range.start,
+            end: range.end,
+        }
+    }
+
+    /// Sets up the walker for the given state from scratch.
+    fn load(&mut self, index: u128) {
+        for (i, bit) in self.state.iter_mut().enumerate() {
+            *bit = (index >> i) & 1 == 1;
+        }
+        let ones = self.state.iter().filter(|&&b| b).count() as i16;
+        self.diff = self.state.len() as i16 - 2 * ones;
+
+        self.size.clear();
+        self.free_labels.clear();
+        self.components = 0;
+        self.next_epoch();
+        for r in 0..self.label.len() {
+            if self.mark[r] != self.epoch {
+                let side = self.search_from(r, 0);
+                let label = self.new_label(self.visited[side].len());
+                for i in 0..self.visited[side].len() {
+                    self.label[self.visited[side][i]] = label;
+                }
+            }
+        }
+    }
+
+    /// Changes the smoothing of one crossing.
+    fn flip(&mut self, crossing: usize) {
+        let (u, v) = self.knot.crossings[crossing].smoothing(self.state[crossing]);
+        let (x, y) = self.knot.crossings[crossing].smoothing(!self.state[crossing]);
+
+        // If the new edge joins two components, removing the old one cannot split anything, so
+        // the components can be merged before the edges change. Otherwise the old edge's removal
+        // is what changes the count, which can only be checked once it is gone.
+        let merging = self.label[x] != self.label[y];
+        if merging {
+            self.merge(x, y);
+        }
+
+        self.state[crossing] = !self.state[crossing];
+        self.diff += if self.state[crossing] { -2 } else { 2 };
+
+        if !merging && u != v {
+            self.split(u, v);
+        }
+    }
+
+    /// Relabels the smaller of the components of `x` and `y` as part of the larger. This must
+    /// happen before the edge between them is added, so that the search stays on one side.
+    fn merge(&mut self, x: usize, y: usize) {
+        let (keep, drop, start) = if self.size[self.label[x]] >= self.size[self.label[y]] {
+            (self.label[x], self.label[y], y)
+        } else {
+            (self.label[y], self.label[x], x)
+        };
+
+        self.next_epoch();
+        let side = self.search_from(start, 0);
+        for i in 0..self.visited[side].len() {
+            self.label[self.visited[side][i]] = keep;
+        }
+        self.size[keep] += self.size[drop];
+        self.free_labels.push(drop);
+        self.components -= 1;
+    }
+
+    /// Checks whether `u` and `v` are still connected now that the edge between them is gone,
+    /// and gives the smaller side a new label if they are not.
+    fn split(&mut self, u: usize, v: usize) {
+        self.next_epoch();
+        for (side, &start) in [u, v].iter().enumerate() {
+            self.queues[side].clear();
+            self.visited[side].clear();
+            self.queues[side].push_back(start);
+            self.visited[side].push(start);
+            self.mark[start] = self.epoch + side as u32;
+        }
+
+        // Search from both ends in lockstep until one side runs out or the two meet.
+        let finished = loop {
+            let mut done = None;
+            for side in 0..2 {
+                match self.step(side) {
+                    Step::Continue => {}
+                    Step::Exhausted => {
+                        done = Some(Some(side));
+                        break;
+                    }
+                    Step::Met => {
+                        done = Some(None);
+                        break;
+                    }
+                }
+            }
+            if let Some(done) = done {
+                break done;
+            }
+        };
+
+        if let Some(side) = finished {
+            let old = self.label[u];
+            let count = self.visited[side].len();
+            let label = self.new_label(count);
+            for i in 0..count {
+                self.label[self.visited[side][i]] = label;
+            }
+            self.size[old] -= count;
+        }
+    }
+
+    /// Expands one region of the search on the given side.
+    fn step(&mut self, side: usize) -> Step {
+        let r = match self.queues[side].pop_front() {
+            Some(r) => r,
+            None => return Step::Exhausted,
+        };
+
+        for k in 0..self.incidence[r].len() {
+            let c = self.incidence[r][k];
+            let (p, q) = self.knot.crossings[c].smoothing(self.state[c]);
+            let next = if p == r {
+                q
+            } else if q == r {
+                p
+            } else {
+                continue;
+            };
+
+            if self.mark[next] == self.epoch + (1 - side) as u32 {
+                return Step::Met;
+            } else if self.mark[next] != self.epoch + side as u32 {
+                self.mark[next] = self.epoch + side as u32;
+                self.queues[side].push_back(next);
+                self.visited[side].push(next);
+            }
+        }
+
+        if self.queues[side].is_empty() {
+            Step::Exhausted
+        } else {
+            Step::Continue
+        }
+    }
+
+    /// Collects the whole component of `start` into `visited[side]`, returning `side`.
+    fn search_from(&mut self, start: usize, side: usize) -> usize {
+        self.queues[side].clear();
+        self.visited[side].clear();
+        self.queues[side].push_back(start);
+        self.visited[side].push(start);
+        self.mark[start] = self.epoch + side as u32;
+        while let Step::Continue = self.step(side) {}
+        side
+    }
+
+    fn new_label(&mut self, size: usize) -> usize {
+        self.components += 1;
+        match self.free_labels.pop() {
+            Some(label) => {
+                self.size[label] = size;
+                label
+            }
+            None => {
+                self.size.push(size);
+                self.size.len() - 1
+            }
+        }
+    }
+
+    /// Starts a new search, so that marks left by earlier searches are ignored.
+    fn next_epoch(&mut self) {
+        // Each search uses two consecutive epoch values, one for each side.
+        if self.epoch >= u32::MAX - 2 {
+            self.mark.iter_mut().for_each(|m| *m = 0);
+            self.epoch = 0;
+        }
+        self.epoch += 2;
+    }
+}
+
+/// An iterator over a range of states in Gray-code order, created by `GrayWalker::states`.
+pub(crate) struct GrayStates<'a> {
+    walker: GrayWalker<'a>,
+    position: u128,
+    end: u128,
+}
+
+impl<'a> Iterator for GrayStates<'a> {
+    type Item = (usize, i16);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.end {
+            return None;
+        }
+
+        // The walker has no components until its first state is loaded.
+        if self.walker.size.is_empty() {
+            self.walker.load(gray(self.position));
+        } else {
+            self.walker.flip(self.position.trailing_zeros() as usize);
+        }
+        self.position += 1;
+        Some((self.walker.components - 1, self.walker.diff))
+    }
+}
+
+enum Step {
+    Continue,
+    Exhausted,
+    Met,
+}
+
+/// Returns the state index at the given position in the Gray-code order.
+pub(crate) fn gray(position: u128) -> u128 {
+    position ^ (position >> 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gray, GrayWalker, StateHistogram};
+    use crate::{
+        polynomial::{Binomial, Polynomial, Term},
+        Knot,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn gray_code() {
+        let codes: Vec<u128> = (0..8).map(gray).collect();
+        assert_eq!(codes, vec![0, 1, 3, 2, 6, 7, 5, 4]);
+        for i in 1..64u128 {
+            assert_eq!((gray(i) ^ gray(i - 1)).count_ones(), 1);
+            assert_eq!(gray(i) ^ gray(i - 1), 1 << i.trailing_zeros());
+        }
+    }
+
+    #[test]
+    fn matches_direct() {
+        for braid in &[
+            "a", "b", "abc", "acb", "bd", "aaa", "abAB", "aBcAbC", "abbaabb", "ace",
+        ] {
+            let knot = Knot::from_str(braid).unwrap();
+            let states = 1u128 << knot.num_crossings();
+            let direct = knot.resolutions();
+
+            let walked: Vec<_> = GrayWalker::new(&knot).states(0..states).collect();
+            assert_eq!(walked.len() as u128, states);
+            for (i, &state) in walked.iter().enumerate() {
+                assert_eq!(
+                    direct[gray(i as u128) as usize],
+                    state,
+                    "{} at {}",
+                    braid,
+                    i
+                );
+            }
+
+            // Walking part of the order starts from the right state.
+            let start = states / 2 + 1;
+            for (i, state) in (start..).zip(GrayWalker::new(&knot).states(start..states)) {
+                assert_eq!(direct[gray(i) as usize], state, "{} at {}", braid, i);
+            }
+        }
+    }
+
+    #[test]
+    fn histogram() {
+        let mut h = StateHistogram::new(3);
+        h.add(2, 3);
+        h.add(2, 3);
+        h.add(1, -1);
+        assert_eq!(h.get(2, 3), 2);
+        assert_eq!(h.get(1, -1), 1);
+        assert_eq!(h.get(1, 1), 0);
+        assert_eq!(h.get(7, 1), 0);
+        // Differences that no state can have are not counted either.
+        for &diff in &[2, -2, 5, -5, i16::MAX, i16::MIN] {
+            assert_eq!(h.get(2, diff), 0, "{}", diff);
+        }
+        assert_eq!(h.iter().collect::<Vec<_>>(), vec![(1, -1, 1), (2, 3, 2)]);
+
+        let mut other = StateHistogram::new(3);
+        other.add(4, -3);
+        other.add(1, -1);
+        let merged = h.merge(other);
+        assert_eq!(merged.total(), 5);
+        assert_eq!(
+            merged.iter().collect::<Vec<_>>(),
+            vec![(1, -1, 2), (2, 3, 2), (4, -3, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn histogram_parity() {
+        StateHistogram::new(3).add(1, 2);
+    }
+
+    #[test]
+    fn bracket() {
+        for braid in &["a", "aaa", "abAB", "aBcAbC", "abbaabb", "ace"] {
+            let knot = Knot::from_str(braid).unwrap();
+            let expected: Polynomial = knot
+                .resolutions()
+                .into_iter()
+                .map(|(c, d)| {
+                    Binomial(Term::new(-1, 2), Term::new(-1, -2)).expand(c as isize - 1)
+                        * Term::new(1, d as isize)
+                })
+                .sum();
+            assert_eq!(knot.state_histogram().bracket_polynomial(), expected);
+        }
+        assert_eq!(
+            StateHistogram::new(2).bracket_polynomial(),
+            Polynomial::zero()
+        );
+    }
+}
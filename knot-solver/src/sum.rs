@@ -0,0 +1,213 @@
+//! Connected sums and disjoint unions of knots and braids.
+//!
+//! Both are made by joining regions of the two diagrams. A disjoint union puts one diagram
+//! inside a region of the other, so those two regions become one. A connected sum cuts an edge
+//! of each diagram and joins the loose ends, so the regions on either side of one edge become
+//! the regions on either side of the other.
+
+use crate::{braid::Braid, Crossing, Knot};
+
+impl Knot {
+    /// Returns the connected sum of the two knots.
+    ///
+    /// The diagrams are joined at the edge between the top and left corners of their first
+    /// crossings, so for links it is the components through those edges that are joined. A
+    /// diagram with no crossings is a set of unknots, and one of them is used up in the sum.
+    ///
+    /// The bracket, beta and jones polynomials of the sum are the products of those of the two
+    /// knots.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let trefoil = Knot::from_str("aaa").unwrap();
+    /// let figure_eight = Knot::from_str("aBaB").unwrap();
+    /// assert_eq!(
+    ///     trefoil.connected_sum(&figure_eight).jones_polynomial(),
+    ///     trefoil.jones_polynomial() * figure_eight.jones_polynomial()
+    /// );
+    /// ```
+    pub fn connected_sum(&self, other: &Knot) -> Knot {
+        let (first, second) = match (self.crossings.first(), other.crossings.first()) {
+            (Some(first), Some(second)) => (first, second),
+            (None, _) => return other.with_circles(self.region_num - 2),
+            (_, None) => return self.with_circles(other.region_num - 2),
+        };
+        let mut next = self.region_num;
+        let mut number = vec![None; other.region_num];
+        number[second.top] = Some(first.top);
+        number[second.left] = Some(first.left);
+        let renumber: Vec<usize> = number
+            .into_iter()
+            .map(|r| {
+                r.unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+        self.joined(other, &renumber, next)
+    }
+
+    /// Returns the disjoint union of the two knots, with the diagram of `other` drawn beside
+    /// this one.
+    ///
+    /// The bracket polynomial of the union is the product of those of the two knots times
+    /// `-A^2 - A^-2`, the bracket of an unlink of two circles.
+    ///
+    /// ```
+    /// use knot_solver::{polynomial::Polynomial, Link};
+    /// use std::str::FromStr;
+    ///
+    /// let trefoil = Link::from_str("aaa").unwrap();
+    /// let union = Link::disjoint_union(&trefoil, &trefoil);
+    /// let circles: Polynomial = "-A^2 - A^-2".parse().unwrap();
+    /// assert_eq!(union.num_crossings(), 6);
+    /// assert_eq!(
+    ///     union.bracket_polynomial(),
+    ///     trefoil.bracket_polynomial() * trefoil.bracket_polynomial() * circles
+    /// );
+    /// ```
+    pub fn disjoint_union(&self, other: &Knot) -> Knot {
+        let renumber: Vec<usize> = (0..other.region_num)
+            .map(|r| if r == 0 { 0 } else { self.region_num + r - 1 })
+            .collect();
+        self.joined(other, &renumber, self.region_num + other.region_num - 1)
+    }
+
+    /// Returns the knot with the given number of unknotted circles drawn beside it.
+    fn with_circles(&self, circles: usize) -> Knot {
+        Knot {
+            crossings: self.crossings.clone(),
+            region_num: self.region_num + circles,
+        }
+    }
+
+    /// Returns the diagram with the crossings of both knots and the given number of regions,
+    /// numbering the regions of `other` as given.
+    fn joined(&self, other: &Knot, renumber: &[usize], region_num: usize) -> Knot {
+        let crossings = self
+            .crossings
+            .iter()
+            .cloned()
+            .chain(other.crossings.iter().map(|c| Crossing {
+                top: renumber[c.top],
+                bottom: renumber[c.bottom],
+                left: renumber[c.left],
+                right: renumber[c.right],
+                orientation: c.orientation,
+            }))
+            .collect();
+        Knot {
+            crossings,
+            region_num,
+        }
+    }
+}
+
+impl Braid {
+    /// Returns the braid with the strands of `other` to the right of this one, so that its
+    /// closure is the disjoint union of the two closures.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// let ab: Braid = "ab".parse().unwrap();
+    /// assert_eq!(ab.disjoint_union(&ab).to_string(), "abde");
+    /// ```
+    pub fn disjoint_union(&self, other: &Braid) -> Braid {
+        let shift = self.strands() as isize;
+        let word = self
+            .word()
+            .iter()
+            .cloned()
+            .chain(other.word().iter().map(|&g| g + g.signum() * shift))
+            .collect();
+        Braid::new(self.strands() + other.strands(), word)
+    }
+
+    /// Returns the disjoint union of the two braids followed by one crossing joining the last
+    /// strand of this braid to the first strand of `other`, so that its closure is the
+    /// connected sum of the two closures.
+    ///
+    /// ```
+    /// use knot_solver::braid::Braid;
+    ///
+    /// let aaa: Braid = "aaa".parse().unwrap();
+    /// assert_eq!(aaa.connected_sum(&aaa).to_string(), "aaacccb");
+    /// ```
+    pub fn connected_sum(&self, other: &Braid) -> Braid {
+        let join = Braid::generator(self.strands() + other.strands(), self.strands());
+        &self.disjoint_union(other) * &join
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{braid::Braid, polynomial::Polynomial, Knot, Link};
+    use std::str::FromStr;
+
+    #[test]
+    fn sums() {
+        let knots: Vec<Knot> = ["aaa", "aBaB", "aaaaa", "abAbcbaCbb", "a", "aA"]
+            .iter()
+            .map(|word| Knot::from_str(word).unwrap())
+            .chain(std::iter::once(
+                Knot::from_pd(&[[1, 5, 2, 4], [3, 1, 4, 6], [5, 3, 6, 2]]).unwrap(),
+            ))
+            .chain(std::iter::once(Knot::from_pd(&[]).unwrap()))
+            .collect();
+        let circles: Polynomial = "-A^2 - A^-2".parse().unwrap();
+        for first in &knots {
+            for second in &knots {
+                let sum = first.connected_sum(second);
+                assert_eq!(
+                    sum.num_crossings(),
+                    first.num_crossings() + second.num_crossings()
+                );
+                assert_eq!(
+                    sum.num_regions() + 2,
+                    first.num_regions() + second.num_regions()
+                );
+                assert_eq!(sum.writhe(), first.writhe() + second.writhe());
+                assert_eq!(
+                    sum.bracket_polynomial(),
+                    first.bracket_polynomial() * second.bracket_polynomial()
+                );
+
+                let union = Link::disjoint_union(first, second);
+                assert_eq!(
+                    union.num_regions() + 1,
+                    first.num_regions() + second.num_regions()
+                );
+                assert_eq!(
+                    union.bracket_polynomial(),
+                    first.bracket_polynomial() * second.bracket_polynomial() * circles.clone()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn braid_sums() {
+        for (first, second) in &[("aaa", "aBaB"), ("ab", "aaa"), ("aa", "AbAb"), ("", "aaa")] {
+            let (a, b): (Braid, Braid) = (first.parse().unwrap(), second.parse().unwrap());
+            let (k, l) = (a.closure(), b.closure());
+            assert_eq!(
+                a.connected_sum(&b).closure().jones_polynomial(),
+                k.connected_sum(&l).jones_polynomial(),
+                "{} # {}",
+                first,
+                second
+            );
+            assert_eq!(
+                a.disjoint_union(&b).closure().bracket_polynomial(),
+                k.disjoint_union(&l).bracket_polynomial(),
+                "{} + {}",
+                first,
+                second
+            );
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Mirror images and reversals of knots.
+//!
+//! A diagram here records only the regions around each crossing and its sign, and the sign
+//! decides which pair of opposite regions the 0 resolution joins. Changing every crossing from
+//! over to under swaps the two pairs, which is the same as flipping every sign. Reversing the
+//! direction of every strand changes neither, and the directions themselves are not recorded,
+//! so it gives back the same diagram.
+
+use crate::{
+    polynomial::{Polynomial, Term},
+    ComputeError, ComputeOptions, Crossing, Knot, Orientation,
+};
+
+/// What the jones polynomial of a knot says about whether it is the same as its mirror image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chirality {
+    /// The jones polynomial changes when the knot is mirrored, so the knot is chiral.
+    Chiral,
+    /// The jones polynomial is the same for the knot and its mirror image. The knot may be
+    /// amphichiral, but some chiral knots, like `9_42`, also have this property.
+    PossiblyAmphichiral,
+}
+
+impl Knot {
+    /// Returns the mirror image of the knot, with every crossing changed.
+    ///
+    /// The bracket polynomial of the mirror image has `A^-1` for every `A`, and the writhe
+    /// changes sign.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let trefoil = Knot::from_str("aaa").unwrap();
+    /// assert_eq!(trefoil.mirror().writhe(), -3);
+    /// assert_eq!(
+    ///     trefoil.mirror().jones_polynomial(),
+    ///     Knot::from_str("AAA").unwrap().jones_polynomial()
+    /// );
+    /// ```
+    pub fn mirror(&self) -> Knot {
+        Knot {
+            crossings: self
+                .crossings
+                .iter()
+                .map(|c| Crossing {
+                    orientation: match c.orientation {
+                        Orientation::Positive => Orientation::Negative,
+                        Orientation::Negative => Orientation::Positive,
+                    },
+                    ..c.clone()
+                })
+                .collect(),
+            region_num: self.region_num,
+        }
+    }
+
+    /// Returns the knot with the direction of every strand reversed, which is also called its
+    /// inverse.
+    ///
+    /// Reversing every strand keeps the sign of every crossing, and the directions of the
+    /// strands are not part of the diagram, so this returns the same diagram. The reverse can
+    /// still be a different knot, as for `8_17`, but no invariant computed here can tell them
+    /// apart. `Braid::reverse` gives a braid whose closure is the reverse as a different word.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let knot = Knot::from_str("abAbcbaCbb").unwrap();
+    /// assert_eq!(knot.reverse().writhe(), knot.writhe());
+    /// ```
+    pub fn reverse(&self) -> Knot {
+        self.clone()
+    }
+
+    /// Compares the jones polynomial of the knot with that of its mirror image.
+    ///
+    /// The jones polynomial of the mirror image has `t^-1` for every `t`, so only one
+    /// polynomial needs to be computed.
+    ///
+    /// ```
+    /// use knot_solver::{Chirality, Knot};
+    /// use std::str::FromStr;
+    ///
+    /// let trefoil = Knot::from_str("aaa").unwrap();
+    /// assert_eq!(trefoil.chirality_hint(), Chirality::Chiral);
+    /// let figure_eight = Knot::from_str("aBaB").unwrap();
+    /// assert_eq!(figure_eight.chirality_hint(), Chirality::PossiblyAmphichiral);
+    /// ```
+    pub fn chirality_hint(&self) -> Chirality {
+        self.try_chirality_hint(&ComputeOptions::new())
+            .expect("no limits were set")
+    }
+
+    /// Compares the jones polynomial of the knot with that of its mirror image, computing it
+    /// with the given options, or returns an error if the computation was stopped early.
+    pub fn try_chirality_hint(&self, options: &ComputeOptions) -> Result<Chirality, ComputeError> {
+        let jones = self.try_jones_polynomial(options)?;
+        let inverse = Polynomial::from(Term::new(1, -1isize)).with_variable('t');
+        let mirrored = jones
+            .substitute(&inverse)
+            .expect("substituting a monic monomial always succeeds");
+        Ok(if mirrored == jones {
+            Chirality::PossiblyAmphichiral
+        } else {
+            Chirality::Chiral
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{braid::Braid, Chirality, Knot};
+    use std::str::FromStr;
+
+    #[test]
+    fn mirrors() {
+        for word in &["aaa", "aBaB", "abAbcbaCbb", "aa", "aA"] {
+            let knot = Knot::from_str(word).unwrap();
+            let mirror = knot.mirror();
+            assert_eq!(mirror.writhe(), -knot.writhe(), "{}", word);
+            assert_eq!(
+                mirror.jones_polynomial(),
+                Knot::from(&word.parse::<Braid>().unwrap().mirror()).jones_polynomial(),
+                "{}",
+                word
+            );
+            assert_eq!(
+                mirror.mirror().bracket_polynomial(),
+                knot.bracket_polynomial()
+            );
+            assert_eq!(
+                knot.reverse().jones_polynomial(),
+                Knot::from(&word.parse::<Braid>().unwrap().reverse()).jones_polynomial(),
+                "{}",
+                word
+            );
+        }
+
+        let trefoil = Knot::from_pd(&[[1, 5, 2, 4], [3, 1, 4, 6], [5, 3, 6, 2]]).unwrap();
+        assert_eq!(trefoil.chirality_hint(), Chirality::Chiral);
+        assert_eq!(trefoil.mirror().chirality_hint(), Chirality::Chiral);
+        let figure_eight =
+            Knot::from_pd(&[[4, 2, 5, 1], [8, 6, 1, 5], [6, 3, 7, 4], [2, 7, 3, 8]]).unwrap();
+        assert_eq!(
+            figure_eight.chirality_hint(),
+            Chirality::PossiblyAmphichiral
+        );
+        // The unknot and the unlink of two circles are their own mirror images.
+        assert_eq!(
+            Knot::from_str("a").unwrap().chirality_hint(),
+            Chirality::PossiblyAmphichiral
+        );
+        assert_eq!(
+            Knot::from_str("aA").unwrap().chirality_hint(),
+            Chirality::PossiblyAmphichiral
+        );
+    }
+}
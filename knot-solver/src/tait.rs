@@ -0,0 +1,563 @@
+use crate::{
+    options::Monitor,
+    polynomial::{Polynomial, Term},
+    ComputeError, ComputeOptions, Knot, RegionCounter,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+/// The checkerboard (Tait) graph of a knot diagram.
+///
+/// The regions of a diagram can be coloured black and white so that the regions on opposite
+/// sides of every crossing share a colour and neighbouring regions do not. The Tait graph has a
+/// vertex for every black region and an edge for every crossing, joining the two black regions
+/// that meet at it. The outer left region is always black, as is any region without a crossing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaitGraph {
+    /// The region of the diagram behind each vertex.
+    regions: Vec<usize>,
+    edges: Vec<TaitEdge>,
+    /// The number of components of the same graph on the white regions, which is one unless the
+    /// diagram is split into several pieces.
+    white_components: usize,
+}
+
+/// An edge of a `TaitGraph`, which stands for one crossing of the diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaitEdge {
+    pub source: usize,
+    pub target: usize,
+    /// `1` if the 0 resolution of the crossing joins the regions at either end of the edge, or
+    /// `-1` if the infinity resolution does.
+    pub sign: i8,
+}
+
+impl Knot {
+    /// Returns the Tait graph of the knot's diagram.
+    pub fn tait_graph(&self) -> TaitGraph {
+        let black = self.checkerboard();
+        let mut vertex = vec![None; self.num_regions()];
+        let regions: Vec<usize> = (0..self.num_regions()).filter(|&r| black[r]).collect();
+        for (i, &r) in regions.iter().enumerate() {
+            vertex[r] = Some(i);
+        }
+
+        let mut white = RegionCounter::new(self.num_regions());
+        let edges = self
+            .crossings
+            .iter()
+            .map(|c| {
+                let ((source, target), (a, b)) = if black[c.top] {
+                    ((c.top, c.bottom), (c.left, c.right))
+                } else {
+                    ((c.left, c.right), (c.top, c.bottom))
+                };
+                white.combine(a, b);
+                TaitEdge {
+                    source: vertex[source].unwrap(),
+                    target: vertex[target].unwrap(),
+                    sign: if c.smoothing(false).0 == source {
+                        1
+                    } else {
+                        -1
+                    },
+                }
+            })
+            .collect();
+
+        TaitGraph {
+            white_components: white.current_count() - regions.len(),
+            regions,
+            edges,
+        }
+    }
+
+    /// Colours the regions of the diagram, returning `true` for every black region.
+    fn checkerboard(&self) -> Vec<bool> {
+        // Pairs of regions that must have the same colour (`true`) or different colours.
+        let mut constraints = vec![Vec::new(); self.num_regions()];
+        for c in &self.crossings {
+            for &(a, b, same) in &[
+                (c.top, c.bottom, true),
+                (c.left, c.right, true),
+                (c.top, c.left, false),
+            ] {
+                constraints[a].push((b, same));
+                constraints[b].push((a, same));
+            }
+        }
+
+        let mut colour = vec![None; self.num_regions()];
+        let mut queue = VecDeque::new();
+        for start in 0..self.num_regions() {
+            if colour[start].is_some() {
+                continue;
+            }
+            colour[start] = Some(true);
+            queue.push_back(start);
+            while let Some(r) = queue.pop_front() {
+                let own = colour[r].unwrap();
+                for &(other, same) in &constraints[r] {
+                    let expected = own == same;
+                    match colour[other] {
+                        None => {
+                            colour[other] = Some(expected);
+                            queue.push_back(other);
+                        }
+                        Some(c) => assert_eq!(c, expected, "diagram is not planar"),
+                    }
+                }
+            }
+        }
+
+        colour.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+impl TaitGraph {
+    /// Returns the number of vertices in the graph.
+    pub fn num_vertices(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns the edges of the graph, in the order of the crossings they stand for.
+    pub fn edges(&self) -> &[TaitEdge] {
+        &self.edges
+    }
+
+    /// Returns the region of the diagram that the given vertex stands for.
+    pub fn region(&self, vertex: usize) -> usize {
+        self.regions[vertex]
+    }
+
+    /// Returns a value that displays the graph as an edge list: a comment line with the number
+    /// of vertices and edges, then one `source target sign` line per edge.
+    ///
+    /// ```
+    /// use knot_solver::Knot;
+    /// use std::str::FromStr;
+    ///
+    /// let graph = Knot::from_str("aaa").unwrap().tait_graph();
+    /// assert_eq!(
+    ///     graph.edge_list().to_string(),
+    ///     "# 2 vertices, 3 edges\n0 1 -1\n0 1 -1\n0 1 -1\n"
+    /// );
+    /// ```
+    pub fn edge_list(&self) -> EdgeList<'_> {
+        EdgeList(self)
+    }
+
+    /// Computes the bracket polynomial of the diagram from its Tait graph.
+    ///
+    /// A state of the diagram corresponds to the set of edges whose crossings are smoothed so
+    /// that they join black regions, and its number of circles depends only on the number of
+    /// components and edges of that subgraph. Each extra piece of a split diagram adds one more
+    /// circle to every state, which is counted by the components of the white graph. The state
+    /// sum is therefore a signed Tutte polynomial of the graph, which is found by deletion and
+    /// contraction. Loops and bridges only contribute a factor of `-A^(±3)`, components are
+    /// computed separately, and the results for subgraphs are remembered so that isomorphic ones
+    /// are only computed once.
+    pub fn bracket_polynomial(&self) -> Polynomial {
+        self.monitored_bracket(&Monitor::new(&ComputeOptions::new(), None))
+            .expect("no limits were set")
+    }
+
+    /// Computes the bracket polynomial, checking the monitor before each subgraph.
+    pub(crate) fn monitored_bracket(&self, monitor: &Monitor) -> Result<Polynomial, ComputeError> {
+        let edges = self
+            .edges
+            .iter()
+            .map(|e| (e.source, e.target, e.sign))
+            .collect();
+        let mut contraction = Contraction::new(monitor);
+        match self.white_components.checked_sub(1) {
+            Some(extra) => {
+                let pieces = contraction.loop_value.pow(extra as u32);
+                Ok(contraction.bracket(self.num_vertices(), edges)? * pieces)
+            }
+            None => {
+                // Without crossings every region is black, and the diagram is an unlink with one
+                // circle fewer than it has regions.
+                let unknots = self
+                    .num_vertices()
+                    .checked_sub(2)
+                    .expect("a diagram without crossings has at least two regions");
+                Ok(contraction.loop_value.pow(unknots as u32))
+            }
+        }
+    }
+}
+
+/// Displays a `TaitGraph` as an edge list, created by `TaitGraph::edge_list`.
+pub struct EdgeList<'a>(&'a TaitGraph);
+
+impl<'a> fmt::Display for EdgeList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(
+            f,
+            "# {} vertices, {} edges",
+            self.0.num_vertices(),
+            self.0.edges.len()
+        )?;
+        for e in &self.0.edges {
+            writeln!(f, "{} {} {}", e.source, e.target, e.sign)?;
+        }
+        Ok(())
+    }
+}
+
+/// An edge given by its endpoints and sign.
+type Edge = (usize, usize, i8);
+
+/// Deletion and contraction with a table of the subgraphs already computed.
+///
+/// For a graph `G` with `V` vertices, this computes `Z(G) / d^(V + 1)`, where `d = -A^2 - A^-2`
+/// and `Z(G)` is the sum over all subsets `S` of the edges of `d^(2k(S))` times `A^s d` for each
+/// edge in `S` and `A^-s` for each edge not in `S`, with `k(S)` the number of components of the
+/// subgraph and `s` the sign of the edge. That normalisation makes the result the bracket
+/// polynomial, and turns the recurrence into
+/// `Z(G) = A^-s Z(G - e) + A^s Z(G / e)` for an edge `e` that is neither a loop nor a bridge.
+struct Contraction<'a> {
+    loop_value: Polynomial,
+    memo: HashMap<Vec<usize>, Polynomial>,
+    monitor: &'a Monitor<'a>,
+}
+
+impl<'a> Contraction<'a> {
+    fn new(monitor: &'a Monitor<'a>) -> Self {
+        Contraction {
+            loop_value: Polynomial::from_vec(vec![Term::new(-1, 2), Term::new(-1, -2)]),
+            memo: HashMap::new(),
+            monitor,
+        }
+    }
+
+    fn bracket(
+        &mut self,
+        vertices: usize,
+        mut edges: Vec<Edge>,
+    ) -> Result<Polynomial, ComputeError> {
+        self.monitor.check()?;
+        // A loop is in every state either way, so it only multiplies by `A^s d + A^-s = -A^3s`.
+        let mut factor = Term::one();
+        edges.retain(|&(u, v, s)| {
+            if u == v {
+                factor = factor * Term::new(-1, 3 * s as isize);
+            }
+            u != v
+        });
+
+        let components = components(vertices, &edges);
+        if components.len() > 1 {
+            // Each component after the first brings another factor of `d`.
+            let extra = self.loop_value.pow((components.len() - 1) as u32);
+            let mut product = extra;
+            for (vertices, edges) in components {
+                product = product * self.bracket(vertices, edges)?;
+            }
+            return Ok(product * factor);
+        }
+        if edges.is_empty() {
+            return Ok(Polynomial::from(factor));
+        }
+
+        let key = canonical_key(vertices, &edges);
+        if let Some(p) = self.memo.get(&key) {
+            return Ok(p.clone() * factor);
+        }
+
+        // Take an edge at a vertex of least degree, which keeps the graphs small and finds
+        // bridges at vertices of degree one without a search.
+        let mut degree = vec![0; vertices];
+        for &(u, v, _) in &edges {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        let (i, _) = edges
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &(u, v, _))| degree[u].min(degree[v]))
+            .unwrap();
+        let (u, v, s) = edges.swap_remove(i);
+        let contracted = contract(u, v, &edges);
+
+        let result = if degree[u] == 1 || degree[v] == 1 || !connected(u, v, vertices, &edges) {
+            // A bridge is never part of a cycle, so deleting it always adds a component, which
+            // leaves `A^s + A^-s d = -A^-3s` times the contraction.
+            self.bracket(vertices - 1, contracted)? * Term::new(-1, -3 * s as isize)
+        } else {
+            self.bracket(vertices, edges)? * Term::new(1, -s as isize)
+                + self.bracket(vertices - 1, contracted)? * Term::new(1, s as isize)
+        };
+
+        self.memo.insert(key, result.clone());
+        self.monitor.advance(1);
+        Ok(result * factor)
+    }
+}
+
+/// Splits a graph into its components, each relabelled to start from vertex 0.
+fn components(vertices: usize, edges: &[Edge]) -> Vec<(usize, Vec<Edge>)> {
+    let mut adjacency = vec![Vec::new(); vertices];
+    for &(u, v, _) in edges {
+        adjacency[u].push(v);
+        adjacency[v].push(u);
+    }
+
+    // The component of every vertex, and its index within that component.
+    let mut place = vec![None; vertices];
+    let mut sizes = Vec::new();
+    for start in 0..vertices {
+        if place[start].is_some() {
+            continue;
+        }
+        let component = sizes.len();
+        let mut size = 0;
+        let mut stack = vec![start];
+        place[start] = Some((component, 0));
+        while let Some(x) = stack.pop() {
+            place[x] = Some((component, size));
+            size += 1;
+            for &y in &adjacency[x] {
+                if place[y].is_none() {
+                    place[y] = Some((component, 0));
+                    stack.push(y);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    let mut result: Vec<(usize, Vec<Edge>)> = sizes.into_iter().map(|n| (n, Vec::new())).collect();
+    for &(u, v, s) in edges {
+        let (component, u) = place[u].unwrap();
+        let (_, v) = place[v].unwrap();
+        result[component].1.push((u, v, s));
+    }
+    result
+}
+
+/// Returns whether `u` and `v` are connected by the given edges.
+fn connected(u: usize, v: usize, vertices: usize, edges: &[Edge]) -> bool {
+    let mut adjacency = vec![Vec::new(); vertices];
+    for &(a, b, _) in edges {
+        adjacency[a].push(b);
+        adjacency[b].push(a);
+    }
+
+    let mut seen = vec![false; vertices];
+    let mut stack = vec![u];
+    seen[u] = true;
+    while let Some(x) = stack.pop() {
+        if x == v {
+            return true;
+        }
+        for &y in &adjacency[x] {
+            if !seen[y] {
+                seen[y] = true;
+                stack.push(y);
+            }
+        }
+    }
+    false
+}
+
+/// Merges `v` into `u`, renumbering the vertices after `v` to close the gap.
+fn contract(u: usize, v: usize, edges: &[Edge]) -> Vec<Edge> {
+    let relabel = |x: usize| {
+        let x = if x == v { u } else { x };
+        if x > v {
+            x - 1
+        } else {
+            x
+        }
+    };
+    edges
+        .iter()
+        .map(|&(a, b, s)| (relabel(a), relabel(b), s))
+        .collect()
+}
+
+/// Describes a graph in a way that does not depend on the order of its vertices in most cases.
+///
+/// The vertices are ordered by colour refinement, which repeatedly distinguishes vertices by the
+/// colours and signs of their neighbours, and ties are broken by the original order. The key
+/// lists the graph in full, so equal keys always mean isomorphic graphs, but some isomorphic
+/// graphs with symmetric vertices can still get different keys.
+fn canonical_key(vertices: usize, edges: &[Edge]) -> Vec<usize> {
+    let mut adjacency = vec![Vec::new(); vertices];
+    for &(u, v, s) in edges {
+        adjacency[u].push((v, s));
+        adjacency[v].push((u, s));
+    }
+
+    let mut colour = vec![0; vertices];
+    let mut classes = 1;
+    loop {
+        let signatures: Vec<(usize, Vec<(usize, i8)>)> = (0..vertices)
+            .map(|x| {
+                let mut neighbours: Vec<(usize, i8)> =
+                    adjacency[x].iter().map(|&(y, s)| (colour[y], s)).collect();
+                neighbours.sort_unstable();
+                (colour[x], neighbours)
+            })
+            .collect();
+        let mut distinct: Vec<&(usize, Vec<(usize, i8)>)> = signatures.iter().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        colour = signatures
+            .iter()
+            .map(|sig| distinct.binary_search(&sig).unwrap())
+            .collect();
+        if distinct.len() == classes {
+            break;
+        }
+        classes = distinct.len();
+    }
+
+    let mut order: Vec<usize> = (0..vertices).collect();
+    order.sort_by_key(|&x| (colour[x], x));
+    let mut position = vec![0; vertices];
+    for (i, &x) in order.iter().enumerate() {
+        position[x] = i;
+    }
+
+    let mut listed: Vec<(usize, usize, i8)> = edges
+        .iter()
+        .map(|&(u, v, s)| {
+            let (a, b) = (position[u], position[v]);
+            (a.min(b), a.max(b), s)
+        })
+        .collect();
+    listed.sort_unstable();
+
+    let mut key = vec![vertices];
+    for (a, b, s) in listed {
+        key.extend_from_slice(&[a, b, (s + 1) as usize]);
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_key, TaitEdge, TaitGraph};
+    use crate::Knot;
+    use std::str::FromStr;
+
+    #[test]
+    fn graph() {
+        // The closure of a two strand braid has a black region on either side of every
+        // crossing, so its Tait graph is a bundle of parallel edges.
+        let graph = Knot::from_str("aaA").unwrap().tait_graph();
+        assert_eq!(graph.num_vertices(), 2);
+        assert_eq!(
+            graph.edges(),
+            &[
+                TaitEdge {
+                    source: 0,
+                    target: 1,
+                    sign: -1
+                },
+                TaitEdge {
+                    source: 0,
+                    target: 1,
+                    sign: -1
+                },
+                TaitEdge {
+                    source: 0,
+                    target: 1,
+                    sign: 1
+                }
+            ]
+        );
+        assert_eq!(graph.region(1), 1);
+
+        // Every crossing is an edge, and every region is a vertex of either this graph or its
+        // dual.
+        for braid in &["abc", "aBcAbC", "abbaabb"] {
+            let knot = Knot::from_str(braid).unwrap();
+            let graph = knot.tait_graph();
+            assert_eq!(graph.edges().len(), knot.num_crossings());
+            assert!(graph.num_vertices() < knot.num_regions());
+        }
+    }
+
+    #[test]
+    fn bracket() {
+        for braid in &[
+            "a",
+            "A",
+            "aaa",
+            "abAB",
+            "aBcAbC",
+            "abbaabb",
+            "abcABC",
+            "aBaBaB",
+            "abcdabcd",
+            "aBcDeFgAbCdEfG",
+        ] {
+            let knot = Knot::from_str(braid).unwrap();
+            assert_eq!(
+                knot.tait_graph().bracket_polynomial(),
+                knot.bracket_polynomial(),
+                "{}",
+                braid
+            );
+        }
+    }
+
+    #[test]
+    fn split_diagrams() {
+        // Missing columns leave regions without crossings and diagrams in several pieces.
+        for braid in &["ac", "c", "ace", "aCe", "bd", "aaCC", "ad", "e"] {
+            let knot = Knot::from_str(braid).unwrap();
+            assert_eq!(
+                knot.tait_graph().bracket_polynomial(),
+                knot.bracket_polynomial(),
+                "{}",
+                braid
+            );
+        }
+    }
+
+    #[test]
+    fn no_crossings() {
+        let knot = Knot::from_pd(&[]).unwrap();
+        assert_eq!(knot.tait_graph().num_vertices(), 2);
+        assert_eq!(
+            knot.tait_graph().bracket_polynomial(),
+            knot.bracket_polynomial()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "a diagram without crossings has at least two regions")]
+    fn too_few_regions() {
+        let graph = TaitGraph {
+            regions: vec![0],
+            edges: Vec::new(),
+            white_components: 0,
+        };
+        graph.bracket_polynomial();
+    }
+
+    #[test]
+    fn keys() {
+        // Relabelling a path or a signed triangle does not change its key.
+        assert_eq!(
+            canonical_key(3, &[(0, 1, 1), (1, 2, -1)]),
+            canonical_key(3, &[(2, 0, -1), (1, 2, 1)])
+        );
+        assert_eq!(
+            canonical_key(3, &[(0, 1, 1), (1, 2, 1), (2, 0, -1)]),
+            canonical_key(3, &[(1, 2, -1), (0, 2, 1), (0, 1, 1)])
+        );
+        assert_ne!(
+            canonical_key(3, &[(0, 1, 1), (1, 2, -1)]),
+            canonical_key(3, &[(0, 1, 1), (1, 2, 1)])
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Tangles, built up crossing by crossing and closed into knot diagrams.
+//!
+//! A tangle is a diagram in a disk with some loose ends on its boundary. Every crossing is
+//! drawn with its strands running diagonally, so the ends around it are at its northwest,
+//! southwest, southeast and northeast, in counterclockwise order. Tangles with four ends use
+//! the same directions for theirs.
+//!
+//! Once all of the loose ends are joined, the components are traced to give a planar diagram
+//! code and the direction of every strand, which fixes the sign of every crossing.
+
+use crate::{braid::Braid, Knot};
+
+/// The directions of the ends of a four-ended tangle and the positions around a crossing, in
+/// counterclockwise order.
+pub(crate) const NW: usize = 0;
+pub(crate) const SW: usize = 1;
+pub(crate) const SE: usize = 2;
+pub(crate) const NE: usize = 3;
+
+/// A place where an arc of a tangle ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    /// A crossing, and the direction of the end from it.
+    Crossing(usize, usize),
+    /// One of the ends of the tangle.
+    Loose(usize),
+}
+
+/// A tangle, given by its crossings and the arcs between them.
+pub(crate) struct Tangle {
+    /// For each crossing, whether the strand from its northwest to its southeast goes over the
+    /// other one.
+    over: Vec<bool>,
+    arcs: Vec<(End, End)>,
+    /// The number of closed arcs, which pass through no crossings.
+    circles: usize,
+}
+
+impl Tangle {
+    /// Creates the tangle with fraction `0`, whose arcs join the northern ends to each other and
+    /// the southern ends to each other.
+    pub(crate) fn zero() -> Self {
+        Tangle {
+            over: Vec::new(),
+            arcs: vec![
+                (End::Loose(NW), End::Loose(NE)),
+                (End::Loose(SW), End::Loose(SE)),
+            ],
+            circles: 0,
+        }
+    }
+
+    /// Creates the tangle with fraction `∞`, whose arcs join the western ends to each other and
+    /// the eastern ends to each other.
+    pub(crate) fn infinity() -> Self {
+        Tangle {
+            over: Vec::new(),
+            arcs: vec![
+                (End::Loose(NW), End::Loose(SW)),
+                (End::Loose(NE), End::Loose(SE)),
+            ],
+            circles: 0,
+        }
+    }
+
+    /// Creates the tangle drawn by a braid, from top to bottom. The strands start at the ends
+    /// `0` to `n - 1` and finish at the ends `n` to `2n - 1`, from left to right.
+    pub(crate) fn braid(braid: &Braid) -> Self {
+        let n = braid.strands();
+        let tangle = Tangle {
+            over: Vec::new(),
+            arcs: (0..n).map(|i| (End::Loose(i), End::Loose(n + i))).collect(),
+            circles: 0,
+        };
+        // A positive crossing has the strand from the northeast on top when both strands run
+        // downwards.
+        braid.word().iter().fold(tangle, |tangle, &g| {
+            let left = n + g.unsigned_abs() - 1;
+            tangle.twist(
+                -g.signum(),
+                [(left, NW), (left + 1, NE)],
+                [(SW, left), (SE, left + 1)],
+            )
+        })
+    }
+
+    /// Adds `n` crossings to the east, each joined to the eastern ends, which adds `n` to the
+    /// fraction.
+    pub(crate) fn twist_eastern(self, n: isize) -> Self {
+        self.twist(n, [(NE, NW), (SE, SW)], [(NE, NE), (SE, SE)])
+    }
+
+    /// Adds `n` crossings to the south, each joined to the southern ends, which adds `n` to one
+    /// over the fraction.
+    pub(crate) fn twist_southern(self, n: isize) -> Self {
+        self.twist(n, [(SW, NW), (SE, NE)], [(SW, SW), (SE, SE)])
+    }
+
+    /// Adds `|n|` crossings, joining the given ends of the tangle to the given positions around
+    /// each one, and then making the given positions on its other side into those ends.
+    fn twist(mut self, n: isize, joined: [(usize, usize); 2], moved: [(usize, usize); 2]) -> Self {
+        for _ in 0..n.unsigned_abs() {
+            let c = self.over.len();
+            self.over.push(n > 0);
+            for &(end, position) in &joined {
+                self.replace(End::Loose(end), End::Crossing(c, position));
+            }
+            for &(position, end) in &moved {
+                self.arcs
+                    .push((End::Crossing(c, position), End::Loose(end)));
+            }
+        }
+        self
+    }
+
+    /// Puts `other` to the east of this tangle, joining the eastern ends of this one to the
+    /// western ends of the other.
+    pub(crate) fn add(self, other: Tangle) -> Self {
+        // The ends of `other` are numbered from 4 until they are joined.
+        let mut sum = self.beside(other, 4);
+        sum.join(NE, NW + 4);
+        sum.join(SE, SW + 4);
+        sum.replace(End::Loose(NE + 4), End::Loose(NE));
+        sum.replace(End::Loose(SE + 4), End::Loose(SE));
+        sum
+    }
+
+    /// Puts `other` next to this tangle without joining them, adding `offset` to the number of
+    /// each of its ends.
+    pub(crate) fn beside(mut self, other: Tangle, offset: usize) -> Self {
+        let crossings = self.over.len();
+        let shift = |end| match end {
+            End::Crossing(c, position) => End::Crossing(c + crossings, position),
+            End::Loose(end) => End::Loose(end + offset),
+        };
+        self.over.extend(other.over);
+        self.arcs
+            .extend(other.arcs.into_iter().map(|(a, b)| (shift(a), shift(b))));
+        self.circles += other.circles;
+        self
+    }
+
+    /// Replaces an end of an arc.
+    fn replace(&mut self, end: End, with: End) {
+        for arc in &mut self.arcs {
+            if arc.0 == end {
+                arc.0 = with;
+            } else if arc.1 == end {
+                arc.1 = with;
+            }
+        }
+    }
+
+    /// Joins two loose ends, so that their arcs become one.
+    fn join(&mut self, first: usize, second: usize) {
+        let mut arc = |end: usize| {
+            let i = self
+                .arcs
+                .iter()
+                .position(|&(a, b)| a == End::Loose(end) || b == End::Loose(end))
+                .expect("every end has an arc");
+            let (a, b) = self.arcs.swap_remove(i);
+            if a == End::Loose(end) {
+                b
+            } else {
+                a
+            }
+        };
+        let a = arc(first);
+        if a == End::Loose(second) {
+            self.circles += 1;
+        } else {
+            let b = arc(second);
+            self.arcs.push((a, b));
+        }
+    }
+
+    /// Returns the numerator closure of a four-ended tangle.
+    pub(crate) fn numerator(self) -> Knot {
+        self.close(&[(NW, NE), (SW, SE)])
+    }
+
+    /// Returns the knot made by joining the given pairs of loose ends, which must be all of
+    /// them, without any arcs crossing.
+    pub(crate) fn close(mut self, pairs: &[(usize, usize)]) -> Knot {
+        for &(first, second) in pairs {
+            self.join(first, second);
+        }
+        if self.over.is_empty() {
+            return Knot {
+                crossings: Vec::new(),
+                region_num: self.circles + 1,
+            };
+        }
+
+        let mut other = vec![[(0, 0); 4]; self.over.len()];
+        for &(a, b) in &self.arcs {
+            if let (End::Crossing(c, p), End::Crossing(d, q)) = (a, b) {
+                other[c][p] = (d, q);
+                other[d][q] = (c, p);
+            }
+        }
+        let mut knot = diagram(&self.over, &other);
+        knot.region_num += self.circles;
+        knot
+    }
+}
+
+/// Returns the diagram given for each crossing by whether the strand from its northwest to its
+/// southeast goes over the other one, and the crossing and position at the other end of the arc
+/// from each of its positions.
+pub(crate) fn diagram(over: &[bool], other: &[[(usize, usize); 4]]) -> Knot {
+    let (code, positive) = signed_planar_diagram_code(over, other);
+    Knot::from_signed_pd(&code, &positive).expect("traced diagrams are planar")
+}
+
+/// Returns the planar diagram code of a diagram given as for `diagram`.
+pub(crate) fn planar_diagram_code(over: &[bool], other: &[[(usize, usize); 4]]) -> Vec<[usize; 4]> {
+    signed_planar_diagram_code(over, other).0
+}
+
+/// Returns the planar diagram code of a diagram given as for `diagram`, and whether each
+/// crossing is positive for the directions the components were traced in.
+fn signed_planar_diagram_code(
+    over: &[bool],
+    other: &[[(usize, usize); 4]],
+) -> (Vec<[usize; 4]>, Vec<bool>) {
+    // Number the arcs along each component, and note which end of each is where it comes into
+    // a crossing.
+    let mut edges = vec![[0; 4]; over.len()];
+    let mut incoming = vec![[false; 4]; over.len()];
+    let mut next = 1;
+    for c in 0..over.len() {
+        for p in 0..4 {
+            let (mut d, mut q) = (c, p);
+            while edges[d][q] == 0 {
+                let (e, r) = other[d][q];
+                edges[d][q] = next;
+                edges[e][r] = next;
+                incoming[e][r] = true;
+                next += 1;
+                d = e;
+                q = (r + 2) % 4;
+            }
+        }
+    }
+    // Each code starts where the under-strand comes in, and the crossing is positive when the
+    // over-strand comes in at the last position.
+    over.iter()
+        .enumerate()
+        .map(|(c, &over)| {
+            let under = if over { SW } else { NW };
+            let start = if incoming[c][under] { under } else { under + 2 };
+            (
+                [0, 1, 2, 3].map(|i| edges[c][(start + i) % 4]),
+                incoming[c][(start + 3) % 4],
+            )
+        })
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tangle, NE, NW, SE, SW};
+    use crate::braid::Braid;
+
+    #[test]
+    fn braids() {
+        for word in &["aaa", "AAA", "aBaB", "abAbcbaCbb", "aA"] {
+            let braid: Braid = word.parse().unwrap();
+            let n = braid.strands();
+            let pairs: Vec<(usize, usize)> = (0..n).map(|i| (i, n + i)).collect();
+            let knot = Tangle::braid(&braid).close(&pairs);
+            let closure = braid.closure();
+            assert_eq!(knot.num_crossings(), braid.len(), "{}", word);
+            assert_eq!(knot.num_regions(), closure.num_regions(), "{}", word);
+            assert_eq!(
+                knot.bracket_polynomial(),
+                closure.bracket_polynomial(),
+                "{}",
+                word
+            );
+        }
+
+        // The components of a link are traced in either direction, which can change the signs
+        // of the crossings between them, but the strand of a knot is followed all the way.
+        for word in &["aaa", "AAA", "aBaB", "aBcaBcaBc", "abC"] {
+            let braid: Braid = word.parse().unwrap();
+            let n = braid.strands();
+            let pairs: Vec<(usize, usize)> = (0..n).map(|i| (i, n + i)).collect();
+            assert_eq!(
+                Tangle::braid(&braid).close(&pairs).writhe(),
+                braid.exponent_sum(),
+                "{}",
+                word
+            );
+        }
+
+        // A braid with no crossings closes to a circle for every strand.
+        let knot = Tangle::braid(&Braid::identity(3)).close(&[(0, 3), (1, 4), (2, 5)]);
+        assert_eq!((knot.num_crossings(), knot.num_regions()), (0, 4));
+    }
+
+    #[test]
+    fn closed_loops() {
+        // Joining the two ends of an arc closes it into a circle.
+        assert_eq!(Tangle::zero().numerator().num_regions(), 3);
+        assert_eq!(Tangle::infinity().numerator().num_regions(), 2);
+
+        let mut tangle = Tangle::zero();
+        tangle.join(NW, NE);
+        assert_eq!((tangle.arcs.len(), tangle.circles), (1, 1));
+        tangle.join(SW, SE);
+        assert_eq!((tangle.arcs.len(), tangle.circles), (0, 2));
+
+        let mut tangle = Tangle::infinity();
+        tangle.join(NW, NE);
+        assert_eq!((tangle.arcs.len(), tangle.circles), (1, 0));
+        tangle.join(SW, SE);
+        assert_eq!((tangle.arcs.len(), tangle.circles), (0, 1));
+    }
+
+    #[test]
+    fn side_by_side() {
+        let pair = || Tangle::zero().beside(Tangle::infinity(), 4);
+        assert_eq!(pair().arcs.len(), 4);
+        // Closing each tangle by itself gives two and one circles.
+        let knot = pair().close(&[(NW, NE), (SW, SE), (NW + 4, NE + 4), (SW + 4, SE + 4)]);
+        assert_eq!((knot.num_crossings(), knot.num_regions()), (0, 4));
+        // Joining them through each other gives one circle.
+        let knot = pair().close(&[(NE, NW + 4), (SE, SW + 4), (NW, NE + 4), (SW, SE + 4)]);
+        assert_eq!(knot.num_regions(), 2);
+        // Circles already closed are carried over.
+        let mut circles = Tangle::zero();
+        circles.join(NW, NE);
+        circles.join(SW, SE);
+        let knot = Tangle::zero().beside(circles, 4).numerator();
+        assert_eq!(knot.num_regions(), 5);
+
+        let twisted = Tangle::zero().beside(Tangle::zero().twist_eastern(1), 4);
+        assert_eq!(twisted.over.len(), 1);
+        let knot = twisted.close(&[(NW, NE), (SW, SE), (NW + 4, NE + 4), (SW + 4, SE + 4)]);
+        assert_eq!((knot.num_crossings(), knot.num_regions()), (1, 5));
+    }
+}